@@ -3,7 +3,6 @@
 mod windows;
 mod wrap_app;
 
-use eframe;
 use egui::ViewportBuilder;
 use wrap_app::WrapApp;
 use crate::windows::settings::{compute_window_size, MAIN_HEIGHT};
@@ -11,7 +10,6 @@ use crate::windows::settings::{compute_window_size, MAIN_HEIGHT};
 /// start function. The window works on the principle of 5x3 rects.
 /// main window is wrap_app, then in hear is main_app, doc_app and setting.
 /// math dir for difraction and other calculation.
-
 fn main() -> eframe::Result {
     let native_options = eframe::NativeOptions {
         viewport: ViewportBuilder::default()
@@ -1,10 +1,11 @@
 use crate::windows::main_app::add_param;
+use crate::windows::math::fresnel::fresnl;
+use crate::windows::math::geometry::Geometry;
 use crate::wrap_app::alloc_ui_block;
 use eframe::emath::Align;
 use egui::text::LayoutJob;
 use egui::{Color32, DragValue, FontId, Hyperlink, RichText, Stroke, TextFormat, Ui, Vec2};
 use egui_plotter::EguiBackend;
-use fresnel::fresnl;
 use plotters::prelude::*;
 use plotters::prelude::full_palette::GREEN_900;
 use plotters::style::full_palette::ORANGE;
@@ -23,6 +24,8 @@ pub struct DocApp {
     l1: f64,
     l2: f64,
     lambda: f64,
+    plane_wave: bool,
+    angle: f64, // degrees
 
     #[cfg(debug_assertions)]
     p: i32,
@@ -68,7 +71,7 @@ impl DocApp {
                     let p = center + (k_axis * i).round() as i32;
                     root.draw(&PathElement::new(
                         [(center - LEN_SHTR, p), (center + LEN_SHTR, p)],
-                        &BLACK,
+                        BLACK,
                     ))
                     .unwrap();
 
@@ -81,7 +84,7 @@ impl DocApp {
 
                     root.draw(&PathElement::new(
                         [(p, center - LEN_SHTR), (p, center + LEN_SHTR)],
-                        &BLACK,
+                        BLACK,
                     ))
                     .unwrap();
 
@@ -98,12 +101,12 @@ impl DocApp {
 
             root.draw(&PathElement::new(
                 [(center, 0), (center, 2 * center)],
-                &BLACK,
+                BLACK,
             ))
             .unwrap();
             root.draw(&PathElement::new(
                 [(0, center), (2 * center, center)],
-                &BLACK,
+                BLACK,
             ))
             .unwrap();
 
@@ -254,12 +257,12 @@ impl DocApp {
 
             root.draw(&PathElement::new(
                 [(center, 0), (center, 2 * center)],
-                &BLACK,
+                BLACK,
             ))
             .unwrap();
             root.draw(&PathElement::new(
                 [(0, center), (2 * center, center)],
-                &BLACK,
+                BLACK,
             ))
             .unwrap();
 
@@ -350,16 +353,27 @@ impl DocApp {
         }
     }
 
+    #[inline]
+    fn geometry(&self) -> Geometry {
+        Geometry::new(self.l1, self.l2, self.lambda, self.plane_wave, self.angle.to_radians())
+    }
+
     #[inline]
     fn k(&self) -> f64 {
-        (2. * (self.l1 + self.l2) / (self.lambda * self.l1 * self.l2)).sqrt()
+        self.geometry().k()
     }
 
+    // u is counted from the direct ray, it is not in the center for oblique wave
     #[inline]
     fn update_u(&mut self) {
-        let k = self.k();
-        self.u1 = self.x1 * k;
-        self.u2 = self.x2 * k;
+        let (k, shadow) = (self.k(), self.geometry().shadow());
+        self.u1 = (self.x1 - shadow) * k;
+        self.u2 = (self.x2 - shadow) * k;
+    }
+
+    #[inline]
+    fn u_to_x(&self, u: f64) -> f64 {
+        u / self.k() + self.geometry().shadow()
     }
 }
 
@@ -380,6 +394,18 @@ impl eframe::App for DocApp {
                                 self.update_u();
                             }
                         });
+                        ui.horizontal(|ui| {
+                            let chng = ui.checkbox(&mut self.plane_wave, "плоская волна").changed();
+                            ui.label("θ:");
+                            let chng = ui
+                                .add(DragValue::new(&mut self.angle).range(-60.0..=60.).suffix("°").speed(0.5))
+                                .changed()
+                                || chng;
+
+                            if chng {
+                                self.update_u();
+                            }
+                        });
 
                         let h = ui.available_height();
                         let size = Vec2::splat(h);
@@ -411,12 +437,12 @@ impl eframe::App for DocApp {
                         let drg = DragValue::new(&mut self.u1).speed(0.05);
                         let ch = add_param(ui, "u1:", drg);
                         if ch {
-                            self.x1 = self.u1 / self.k();
+                            self.x1 = self.u_to_x(self.u1);
                         }
                         let drg = DragValue::new(&mut self.u2).speed(0.05);
                         let ch = add_param(ui, "u2:", drg);
                         if ch {
-                            self.x2 = self.u2 / self.k();
+                            self.x2 = self.u_to_x(self.u2);
                         }
 
                         ui.separator();
@@ -522,6 +548,8 @@ impl Default for DocApp {
             l1: 30.,
             l2: 30.,
             lambda: 3.,
+            plane_wave: false,
+            angle: 0.,
             #[cfg(debug_assertions)]
             p: 10,
             dn: false,
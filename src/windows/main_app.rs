@@ -1,11 +1,22 @@
-use crate::windows::math::calc::converter_freq;
 use crate::windows::math::chart::ChartParams;
-use crate::windows::math::difr::{Difr, Screens, MAX_X};
+use crate::windows::math::circular::{round_arrow, vibration_curve};
+use crate::windows::math::phasors::{strip_phasors, to_plot_frame, zone_phasors, ZoneScreen};
+use crate::windows::math::difr::{Curves, Difr, Model, Scan, Screens, ZoneOutline, MAX_X};
+use crate::windows::math::features::FeatureKind;
+use crate::windows::math::fringes::{fit, FringeParams, Unknown};
+use crate::windows::math::geometry::{Beam, Geometry};
+use crate::windows::math::pulse::{waveforms, Pulse, PulseParams};
+use crate::windows::math::spectrum::Spectrum;
+use crate::windows::math::transmission::{Interval, Preset};
+use crate::windows::math::units::{FreqUnit, Frequency, Length, LengthUnit};
+use crate::windows::math::worker::Worker;
+use crate::windows::math::zone_plate::{dxf, on_axis, radii, svg, Plate, ZonePlateParams};
 use crate::windows::settings::{COLS, OFFEST_X, OFFEST_Y, ROWS};
 use crate::wrap_app::alloc_ui_block;
 use egui::{Button, Color32, DragValue, Event, Image, Pos2, Rect, Ui, Vec2};
-use egui_plot::{AxisHints, GridInput, GridMark, Line, MarkerShape, Plot, PlotPoint, Points};
+use egui_plot::{Arrows, AxisHints, GridInput, GridMark, Line, MarkerShape, Plot, PlotImage, PlotPoint, Points};
 use egui_plotter::EguiBackend;
+use num_complex::Complex64;
 use plotters::prelude::*;
 use plotters::style::full_palette::GREY_500;
 use std::default::Default;
@@ -23,12 +34,60 @@ const COLOR_PROEKCIA_LILGHT: RGBColor = RGBColor(0, 139, 0);
 const COLOR_PROEKCIA_DARK: RGBColor = RGBColor(173, 255, 173);
 const COLOR_RED_POINT: RGBColor = RGBColor(212, 0, 0);
 const COLOR_RED_POINT_EGUI: Color32 = Color32::from_rgb(212, 0, 0);
+const COLOR_MODEL_EGUI: Color32 = Color32::from_rgb(0, 139, 0);
+const COLOR_APERTURE_EGUI: Color32 = Color32::from_rgb(148, 0, 211);
+const COLOR_SCREEN: RGBColor = RGBColor(255, 140, 0); // pieces of the spiral behind a transparent screen
+const COLOR_SPECTRUM_EGUI: Color32 = Color32::from_rgb(0, 150, 150);
+const COLOR_FEATURES_EGUI: Color32 = Color32::from_rgb(200, 0, 120);
+
+const SPIRAL_SPEED: f32 = 1.; // growth of the hole in the animation, cm/s
+const MAX_PHASORS: usize = 500; // arrows of zones in the diagram
+const MIN_ZONE_PX: f32 = 2.; // thinner zones are shown by their density
+const DENSITY_BAND_PX: f32 = 3.;
+const MAX_FEATURE_LABELS: usize = 12; // more points on the plot are not signed
 
 #[derive(PartialEq, Default)]
 enum ScreenMod {
     #[default]
     Rectangle,
-    Circle,
+    Circle, // round hole of radius x
+    Disk,   // round disk of radius x
+}
+
+// map of the field behind the screen, the picture is remade when Difr is rebuilt
+struct MapParams {
+    show: bool,
+    phase: bool,
+    z_max: Length,
+    texture: Option<egui::TextureHandle>,
+    asked: Option<(u64, bool, Length)>, // what the last job of the worker is for
+    worker: Worker<Vec<(f64, f64)>>,
+}
+
+impl Default for MapParams {
+    fn default() -> Self {
+        Self {
+            show: false,
+            phase: false,
+            z_max: Length::from_cm(80.),
+            texture: None,
+            asked: None,
+            worker: Worker::new(),
+        }
+    }
+}
+
+// mean of blue and red zones, darker where more zones fall on a pixel
+fn density_color(per_px: f32) -> RGBColor {
+    let f = 1. / (1. + per_px.max(1.).ln() / 2.);
+    RGBColor((128. * f) as u8, 0, (128. * f) as u8)
+}
+
+// which of the bottom plots is drawn
+#[derive(PartialEq, Copy, Clone)]
+enum BottomPlot {
+    Abs,
+    Arg,
 }
 
 #[derive(Default)]
@@ -37,8 +96,25 @@ pub struct MainApp {
 
     fz: Difr,
     is_freq: bool,
+    length_unit: LengthUnit, // units of the panel, the model is in cm and ГГц anyway
+    freq_unit: FreqUnit,
     zoom: bool,
     screen_mod: ScreenMod,
+    show_diff: bool, // model minus Fresnel instead of curves
+    pulse: PulseParams,
+    map: MapParams,
+    zone_plate: ZonePlateParams,
+    spiral_anim: bool, // hole (or disk) grows by itself
+    show_phasors: bool,
+    show_features: bool,
+    mark_features: bool, // extrema and other points on the bottom plots
+    fringes: FringeParams,
+    linear_zones: bool, // strips instead of rings for straight edges
+    exact_zones: (Option<(Geometry, f32)>, Vec<ZoneOutline>), // zones off the axis and what was asked for them
+    zones: Worker<Vec<ZoneOutline>>,
+    curves: Worker<Curves>,
+    busy_since: Option<f64>, // time when the worker started the curves
+    hovered_zone: Option<usize>, // zone under the mouse in the picture, from 1
 
     #[cfg(debug_assertions)]
     p: f64,
@@ -152,11 +228,7 @@ impl MainApp {
             chpr.yaw = (chpr.yaw * ZERS).round() / ZERS;
             chpr.pitch = (chpr.pitch * ZERS).round() / ZERS;
 
-            if chpr.pitch >= FRAC_PI_2 {
-                chpr.pitch = FRAC_PI_2;
-            } else if chpr.pitch <= -FRAC_PI_2 {
-                chpr.pitch = -FRAC_PI_2;
-            }
+            chpr.pitch = chpr.pitch.clamp(-FRAC_PI_2, FRAC_PI_2);
 
             // Next plot everything
             let root = EguiBackend::new(ui).into_drawing_area();
@@ -268,8 +340,8 @@ impl MainApp {
                 .draw()
                 .unwrap();
 
-            let points = match self.fz.rezhim {
-                Screens::One => {
+            let points = match (self.fz.rezhim, self.fz.screen_amp > 0.) {
+                (Screens::One, false) => {
                     let mut v = vec![(0.0, 0.0, 0.0), self.fz.get_current_point_3d()];
                     chart
                         .draw_series(LineSeries::new(v.clone(), &BLACK))
@@ -277,7 +349,7 @@ impl MainApp {
                     v.remove(0);
                     v
                 }
-                Screens::Two => {
+                (Screens::Two, false) => {
                     let (point1, point2) = self.fz.get_current_points_3d();
 
                     let v = vec![point1, point2];
@@ -286,6 +358,30 @@ impl MainApp {
                         .unwrap();
                     v
                 }
+                _ => {
+                    // every interval is its own piece of the spiral and its chord,
+                    // the chord of a transparent part is turned and shortened by t
+                    let segments = self.fz.get_current_segments_3d();
+                    for (t, [a, b]) in segments.iter() {
+                        let open = (t - 1.).norm() < 1e-6;
+                        let color = if open { COLOR_RED_POINT } else { COLOR_SCREEN };
+                        let arc = line.iter().copied().filter(|p| p.0 >= a.0 && p.0 <= b.0);
+                        chart
+                            .draw_series(LineSeries::new(arc, color.stroke_width(3)))
+                            .unwrap();
+                        chart
+                            .draw_series(LineSeries::new([*a, *b], &BLACK))
+                            .unwrap();
+                        if !open {
+                            let chord = t * Complex64::new(b.1 - a.1, b.2 - a.2);
+                            let end = (b.0, a.1 + chord.re, a.2 + chord.im);
+                            chart
+                                .draw_series(DashedLineSeries::new([*a, end], 5, 5, BLACK.into()))
+                                .unwrap();
+                        }
+                    }
+                    segments.into_iter().flat_map(|(_, ends)| ends).collect()
+                }
             };
 
             // draw red point
@@ -304,7 +400,7 @@ impl MainApp {
 
                 let mut phi = (chpr.yaw) % (2. * PI);
                 if phi < 0. {
-                    phi += 2. * 3.14;
+                    phi += 2. * PI;
                 }
 
                 // println!("{:>5}|{phi:>5}", chpr.yaw);
@@ -314,12 +410,8 @@ impl MainApp {
                 } else {
                     COLOR_PROEKCIA_LILGHT
                 };
-                if phi != 3.14 && phi != 0. {
-                    let p = if phi < PI {
-                        -MAX_X as f64 * self.fz.k()
-                    } else {
-                        MAX_X as f64 * self.fz.k()
-                    };
+                if phi != PI && phi != 0. {
+                    let p = if phi < PI { st } else { end };
 
                     let l = line.iter().map(|&(_, y, z)| (p, y, z));
                     chart
@@ -367,7 +459,7 @@ impl MainApp {
                 }
 
                 let phi = phi - FRAC_PI_2;
-                if phi != 0. && phi != 3.14 {
+                if phi != 0. && phi != PI {
                     let p = -if phi > 0. && phi < PI { 1. } else { -1. };
 
                     let l = line.iter().map(|&(x, y, _)| (x, y, p));
@@ -423,18 +515,37 @@ impl MainApp {
             ui.horizontal(|ui| {
                 let (name, chang) = match fz.rezhim {
                     Screens::One => ("1", Screens::Two),
-                    Screens::Two => ("2", Screens::One),
+                    Screens::Two => ("2", Screens::Intervals),
+                    Screens::Intervals => ("интервалы", Screens::Grating),
+                    Screens::Grating => ("решётка", Screens::One),
                 };
                 ui.label("количество экранов: ");
                 if ui.button(name).clicked() {
                     fz.rezhim = chang;
+                    if !fz.model.supports(chang, fz.scan, fz.screen_amp) {
+                        fz.model = Model::Fresnel;
+                    }
                     fz.rebuild_integrals();
                     self.screen_mod = ScreenMod::Rectangle;
                 }
 
+                // strips or rings of zones for straight edges
+                if matches!(fz.rezhim, Screens::One | Screens::Two)
+                    && self.screen_mod == ScreenMod::Rectangle
+                {
+                    let name = if self.linear_zones { "зоны: полосы" } else { "зоны: кольца" };
+                    if ui.button(name).on_hover_text("вид зон Френеля на картинке").clicked() {
+                        self.linear_zones = !self.linear_zones;
+                    }
+                }
+
                 if Screens::One == fz.rezhim {
                     let (img, chang) = match self.screen_mod {
                         ScreenMod::Circle => (
+                            egui::include_image!("../../imgs/disk.svg"),
+                            ScreenMod::Disk,
+                        ),
+                        ScreenMod::Disk => (
                             egui::include_image!("../../imgs/rect.svg"),
                             ScreenMod::Rectangle,
                         ),
@@ -450,19 +561,72 @@ impl MainApp {
                 }
             });
 
+            // what is moved: the edge or the receiver
+            ui.horizontal(|ui| {
+                let (name, chang) = match fz.scan {
+                    Scan::Edge => ("кромка", Scan::Receiver),
+                    Scan::Receiver => ("приёмник", Scan::Edge),
+                };
+                ui.label("двигается: ");
+                if ui.button(name).clicked() {
+                    fz.scan = chang;
+                    if !fz.model.supports(fz.rezhim, chang, fz.screen_amp) {
+                        fz.model = Model::Fresnel;
+                    }
+                    fz.rebuild_integrals();
+                }
+            });
+
             //len from center to screen
             ui.horizontal(|ui| {
                 let start = fz.get_start();
+                let (name, hint) = match fz.rezhim {
+                    Screens::Intervals => ("сдвиг:", "смещение всех интервалов экрана"),
+                    Screens::Grating => ("центр:", "положение центра решётки"),
+                    _ => ("x:", "расстояние от центра окна до кромки экрана"),
+                };
 
-                ui.label("x:")
-                    .on_hover_text("расстояние от центра окна до кромки экрана");
-                ui.add(
-                    DragValue::new(&mut fz.x_otv)
-                        .range(start..=MAX_X)
-                        .suffix("см")
-                        .speed(0.1),
-                )
-                .on_hover_text("расстояние от центра окна до кромки экрана");
+                ui.label(name).on_hover_text(hint);
+                let range = start as f64..=MAX_X as f64;
+                ui.add(length_drag(&mut fz.x_otv, self.length_unit, range, 0.1))
+                    .on_hover_text(hint);
+            });
+            if fz.rezhim == Screens::Intervals {
+                Self::intervals_ui(fz, self.length_unit, ui);
+            } else {
+                if fz.rezhim == Screens::Grating {
+                    Self::grating_ui(fz, self.length_unit, ui);
+                }
+                // dielectric sheets and absorbers let a part of the wave through
+                ui.horizontal(|ui| {
+                    let hint = "пропускание экрана: 0 - металл, у диэлектрика или поглотителя больше";
+                    ui.label("экран |t|:").on_hover_text(hint);
+                    let amp = ui
+                        .add(DragValue::new(&mut fz.screen_amp).range(0.0..=1.).speed(0.01))
+                        .on_hover_text(hint);
+                    if amp.changed() && !fz.model.supports(fz.rezhim, fz.scan, fz.screen_amp) {
+                        fz.model = Model::Fresnel;
+                    }
+                    ui.label("φ:").on_hover_text("сдвиг фазы волны, прошедшей через экран");
+                    ui.add(
+                        DragValue::new(&mut fz.screen_phase)
+                            .range(-180.0..=180.)
+                            .suffix("°")
+                            .speed(1.),
+                    );
+                });
+            }
+
+            // units of the panel
+            ui.horizontal(|ui| {
+                ui.label("единицы:");
+                for unit in LengthUnit::ALL {
+                    ui.selectable_value(&mut self.length_unit, unit, unit.name());
+                }
+                ui.separator();
+                for unit in FreqUnit::ALL {
+                    ui.selectable_value(&mut self.freq_unit, unit, unit.name());
+                }
             });
 
             // frequency/len of vawe
@@ -470,34 +634,25 @@ impl MainApp {
                 let name = if self.is_freq { "f:" } else { "λ:" };
                 ui.label(name);
 
-                let drag = if self.is_freq {
-                    DragValue::new(&mut fz.freq)
-                        .range(6.0..=20.)
-                        .suffix("ГГц")
-                        .speed(0.1)
-                } else {
-                    DragValue::new(&mut fz.lambda)
-                        .range(0.1..=5.)
-                        .suffix("см")
-                        .speed(0.1)
-                };
-                ui.add(drag);
-
                 if self.is_freq {
-                    fz.lambda = converter_freq(fz.freq);
+                    // the frequency is not kept, λ is changed only by the user
+                    let mut freq = fz.freq();
+                    if ui.add(freq_drag(&mut freq, self.freq_unit, 6.0..=20., 0.1)).changed() {
+                        fz.lambda = freq.wavelength();
+                    }
                 } else {
-                    fz.freq = converter_freq(fz.lambda);
+                    ui.add(length_drag(&mut fz.lambda, self.length_unit, 0.1..=5., 0.1));
                 }
 
-                const MIN_LAMBDA: f32 = 0.1;
+                const MIN_LAMBDA: Length = Length::from_cm(0.1);
                 if fz.lambda < MIN_LAMBDA {
                     fz.lambda = MIN_LAMBDA;
                 }
 
                 let name = if !self.is_freq {
-                    "в ГГц"
+                    format!("в {}", self.freq_unit.name())
                 } else {
-                    "в см"
+                    format!("в {}", self.length_unit.name())
                 };
                 if ui.button(name).clicked() {
                     self.is_freq = !self.is_freq;
@@ -505,19 +660,35 @@ impl MainApp {
             });
 
             // len of rupr to screen
-            let drag = DragValue::new(&mut fz.l1)
-                .range(0.1..=50.)
-                .suffix("см")
-                .speed(0.1);
-            add_param(ui, "📢 ~ ||     L1:", drag);
+            ui.add_enabled_ui(!fz.plane_wave, |ui| {
+                let drag = length_drag(&mut fz.l1, self.length_unit, 0.1..=50., 0.1);
+                add_param(ui, "📢 ~ ||     L1:", drag);
+            });
+
+            // where the wave comes from
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut fz.plane_wave, "плоская волна")
+                    .on_hover_text("источник бесконечно далеко (L1 → ∞)");
+                ui.label("θ:")
+                    .on_hover_text("угол падения, от оси к кромке экрана");
+                ui.add(
+                    DragValue::new(&mut fz.angle)
+                        .range(-60.0..=60.)
+                        .suffix("°")
+                        .speed(0.5),
+                )
+                .on_hover_text("угол падения, от оси к кромке экрана");
+            });
 
             //len of screen to rupr
-            let drag = DragValue::new(&mut fz.l2)
-                .range(0.1..=50.)
-                .suffix("см")
-                .speed(0.1);
+            let drag = length_drag(&mut fz.l2, self.length_unit, 0.1..=50., 0.1);
             add_param(ui, "|| ~ 📢     L2:", drag);
 
+            // receiver offset from the axis
+            let range = -MAX_X as f64..=MAX_X as f64;
+            let drag = length_drag(&mut fz.x_rec, self.length_unit, range, 0.1);
+            add_param(ui, "📢 приёмник  x:", drag);
+
             // helper
             #[cfg(debug_assertions)]
             {
@@ -528,27 +699,101 @@ impl MainApp {
 
         // detect of update of params
         if fz.is_cheng() {
-            if fz.not_sale_cpu_usage() {
-                fz.cheng_copes();
-                fz.rebuild_integrals()
-            } else {
-                fz.backup_copes()
+            fz.cheng_copes();
+            fz.rebuild_integrals()
+        }
+    }
+
+    // vibration curve of the round hole (or disk) of radius x centered on the screen,
+    // the arrow is the field in the receiver relative to the free wave
+    fn draw_spiral(&mut self, ui: &mut Ui) {
+        let disk = self.screen_mod == ScreenMod::Disk;
+
+        if self.spiral_anim {
+            let step = SPIRAL_SPEED * ui.input(|i| i.stable_dt).min(0.1);
+            self.fz.x_otv = Length::from_cm(self.fz.x_otv.cm() + step as f64);
+            if self.fz.x_otv.cm() > MAX_X as f64 {
+                self.fz.x_otv = Length::from_cm(0.);
             }
+            ui.ctx().request_repaint();
         }
+
+        let a = self.fz.x_otv.cm().max(0.);
+        let curve = vibration_curve(&self.fz.geometry(), (MAX_X * SQRT_2) as f64);
+        let (from, to) = round_arrow(&curve, a, self.fz.screen_t(), disk);
+        let intensity = (to - from).norm_sqr();
+
+        let points = |part: &[(f64, Complex64)]| -> Vec<[f64; 2]> {
+            part.iter().map(|p| [p.1.re, p.1.im]).collect()
+        };
+        let split = curve.partition_point(|p| p.0 < a);
+        let (inner, outer) = curve.split_at(split);
+        let (open, closed) = match disk {
+            false => (inner, outer),
+            true => (outer, inner),
+        };
+
+        ui.horizontal(|ui| {
+            let name = if self.spiral_anim { "⏸" } else { "▶" };
+            let hint = if disk { "увеличивать диск" } else { "открывать отверстие" };
+            if ui.button(name).on_hover_text(hint).clicked() {
+                self.spiral_anim = !self.spiral_anim;
+                if self.fz.x_otv.cm() < 0. {
+                    self.fz.x_otv = Length::from_cm(0.);
+                }
+            }
+            let (r, unit) = (Length::from_cm(a).in_unit(self.length_unit), self.length_unit.name());
+            let text = match disk {
+                false => format!("отверстие r = {r:.2}{unit}, I/I0 = {intensity:.3}"),
+                true => format!("диск r = {r:.2}{unit}, пятно Пуассона I/I0 = {intensity:.3}"),
+            };
+            ui.label(text);
+        });
+
+        Plot::new("vibration_spiral")
+            .data_aspect(1.)
+            .allow_drag(false)
+            .allow_scroll(false)
+            .x_axis_label("Re")
+            .y_axis_label("Im")
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new("", points(closed)).color(Color32::GRAY));
+                plot_ui.line(Line::new("", points(open)).color(Color32::BLUE).width(2.));
+                plot_ui.arrows(
+                    Arrows::new("", vec![[from.re, from.im]], vec![[to.re, to.im]])
+                        .color(COLOR_RED_POINT_EGUI),
+                );
+                plot_ui.points(
+                    Points::new("", vec![[from.re, from.im], [to.re, to.im]])
+                        .radius(3.)
+                        .color(COLOR_RED_POINT_EGUI),
+                );
+            });
     }
 
     fn draw_wave(&mut self, ui: &mut Ui) {
         let root_size = ui.available_width();
+        let origin = ui.max_rect().min;
+        let mouse = ui.input(|i| i.pointer.hover_pos()).filter(|p| ui.max_rect().contains(*p));
 
         let root = EguiBackend::new(ui).into_drawing_area();
 
         let center = (root_size / 2.).round();
-        let center_of_circle = (center as i32, center as i32);
 
         let root_k = center / MAX_X;
+        let x_otv = self.fz.x_otv.cm() as f32; // the picture is in f32
+
+        // zones are around the direct ray, for oblique wave it is not the center
+        let center_of_circle = (
+            (center + self.fz.zones_center() * root_k).round() as i32,
+            center as i32,
+        );
 
         // if screen is close
-        if self.fz.get_start() == self.fz.x_otv {
+        if self.fz.rezhim != Screens::Intervals
+            && self.screen_mod != ScreenMod::Disk
+            && self.fz.get_start() == x_otv
+        {
             root.fill(&BLACK).unwrap();
             return;
         }
@@ -557,66 +802,261 @@ impl MainApp {
             root.fill(&BLACK).unwrap();
         }
 
-        // radius of waves
-        let waves = self
-            .fz
-            .get_fresnel_zones(self.screen_mod == ScreenMod::Circle)
-            .into_iter();
-        let max_n = waves.len() - 1;
-        let mut last_r = 0.0;
-        for (n, r) in waves.into_iter().enumerate() {
-            if self.screen_mod == ScreenMod::Circle && r >= self.fz.x_otv {
+        let strips = self.strip_zones();
+        let exact = !strips && self.fz.off_axis();
+        // zones thinner than this are not drawn one by one
+        let min_width = MIN_ZONE_PX / root_k;
+        let stamp = Some((self.fz.geometry(), min_width));
+        // the zones are made by the worker, the old ones are drawn until then
+        if exact && self.exact_zones.0 != stamp {
+            let fz = self.fz.clone();
+            self.zones.submit(move |job| fz.get_exact_zones(min_width, job));
+            self.exact_zones.0 = stamp;
+        }
+        if let Some(zones) = self.zones.poll() {
+            self.exact_zones.1 = zones;
+        }
+        if self.zones.busy() {
+            ui.ctx().request_repaint();
+        }
+        // point of the screen in cm to the picture
+        let to_px = |(x, y): (f32, f32)| ((center + x * root_k) as i32, (center + y * root_k) as i32);
+        // x of the strip between distances d1 and d2 from the center of zones on the side
+        let strip = |d1: f32, d2: f32, side: f32| {
+            let x1 = (center_of_circle.0 as f32 + side * d1 * root_k) as i32;
+            let x2 = (center_of_circle.0 as f32 + side * d2 * root_k) as i32;
+            [(x1.min(x2), 0), (x1.max(x2), root_size as i32)]
+        };
+
+        // beyond the thin zones the picture is shaded by their density
+        let lod = match strips {
+            true => self.fz.lod_strip(min_width),
+            false => self.fz.lod_radius(min_width),
+        };
+        if let Some(lod) = lod {
+            let c = self.fz.zones_center().abs();
+            let (b, mut far) = match strips {
+                true => (2. / (self.fz.geometry().k() as f32).powi(2), MAX_X + c),
+                false => (self.fz.geometry().b() as f32, MAX_X * SQRT_2 + c),
+            };
+            if self.screen_mod == ScreenMod::Circle {
+                far = far.min(self.fz.zones_clip());
+            }
+            // from the edge to the center, the width of a zone at r is b/(2r)
+            let mut r = far;
+            while r > lod {
+                let style = ShapeStyle::from(&density_color(2. * r / (b * root_k))).filled();
+                match strips {
+                    true => root.draw(&Rectangle::new(strip(-r, r, 1.), style)).unwrap(),
+                    false => root.draw(&Circle::new(center_of_circle, r * root_k, style)).unwrap(),
+                }
+                r -= DENSITY_BAND_PX / root_k;
+            }
+        }
+
+        if strips {
+            // strips of zones on both sides, odd ones add and even ones subtract
+            let edges = self.fz.get_strip_zones(min_width);
+            for (n, d) in edges.windows(2).enumerate() {
+                let (color, sign) = if n % 2 == 0 { (&BLUE, "+") } else { (&RED, "−") };
+                for side in [-1., 1.] {
+                    let [p1, p2] = strip(d[0], d[1], side);
+                    root.draw(&Rectangle::new([p1, p2], ShapeStyle::from(color).filled()))
+                        .unwrap();
+                    if p2.0 - p1.0 > 12 {
+                        let font = ("sans-serif", 14).into_font().color(&WHITE);
+                        root.draw(&Text::new(sign, ((p1.0 + p2.0) / 2 - 4, 4), font)).unwrap();
+                    }
+                }
+            }
+        } else if exact {
+            // zones off the axis, outer first, odd ones are blue as the rings
+            let count = self.exact_zones.1.len();
+            for (i, zone) in self.exact_zones.1.iter().enumerate() {
+                let color = if (count - i) % 2 == 1 { &BLUE } else { &RED };
+                let points: Vec<(i32, i32)> = zone.iter().map(|&p| to_px(p)).collect();
+                root.draw(&Polygon::new(points, ShapeStyle::from(color).filled())).unwrap();
+            }
+        } else {
+            // radius of waves
+            let waves = self
+                .fz
+                .get_fresnel_zones(self.screen_mod == ScreenMod::Circle, min_width)
+                .into_iter();
+            let max_n = waves.len() - 1;
+            let mut last_r = 0.0;
+            for (n, r) in waves.into_iter().enumerate() {
+                if self.screen_mod == ScreenMod::Circle && r >= self.fz.zones_clip() {
+                    last_r = r;
+                    continue;
+                }
+                let color = if (n + max_n).is_multiple_of(2) { &BLUE } else { &RED };
+                root.draw(&Circle::new(
+                    center_of_circle,
+                    last_r * root_k,
+                    ShapeStyle::from(color).filled(),
+                ))
+                .unwrap();
                 last_r = r;
-                continue;
             }
-            let color = if (n + max_n) % 2 == 0 { &BLUE } else { &RED };
+            // its wave too
             root.draw(&Circle::new(
                 center_of_circle,
                 last_r * root_k,
-                ShapeStyle::from(color).filled(),
+                ShapeStyle::from(&RED).filled(),
+            ))
+            .unwrap();
+        }
+
+        // zone under the mouse
+        let b = self.fz.geometry().b() as f32;
+        let k = self.fz.geometry().k() as f32;
+        self.hovered_zone = mouse.map(|p| {
+            let dx = p.x - origin.x - center_of_circle.0 as f32;
+            let dy = p.y - origin.y - center_of_circle.1 as f32;
+            if exact {
+                let x = (p.x - origin.x - center) / root_k;
+                let y = (p.y - origin.y - center) / root_k;
+                return self.fz.zone_at(x, y);
+            }
+            let n = match strips {
+                true => (k * dx / root_k).powi(2) / 2.,
+                false => dx.hypot(dy).powi(2) / root_k.powi(2) / b,
+            };
+            (n.ceil() as usize).max(1)
+        });
+        if let (Some(n), true) = (self.hovered_zone, exact) {
+            // boundaries of the zone, the first one has only the outer
+            let count = self.exact_zones.1.len();
+            for m in [n, n - 1] {
+                if let Some(zone) = count.checked_sub(m).and_then(|i| self.exact_zones.1.get(i)) {
+                    let mut points: Vec<(i32, i32)> = zone.iter().map(|&p| to_px(p)).collect();
+                    points.extend(points.first().copied());
+                    root.draw(&PathElement::new(points, YELLOW.stroke_width(3))).unwrap();
+                }
+            }
+        } else if let (Some(n), true) = (self.hovered_zone, strips) {
+            let (d1, d2) = ((2. * (n - 1) as f32).sqrt() / k, (2. * n as f32).sqrt() / k);
+            for side in [-1., 1.] {
+                root.draw(&Rectangle::new(strip(d1, d2, side), YELLOW.mix(0.6).filled()))
+                    .unwrap();
+            }
+        } else if let Some(n) = self.hovered_zone {
+            let (r_in, mut r_out) = (((n - 1) as f32 * b).sqrt(), (n as f32 * b).sqrt());
+            if self.screen_mod == ScreenMod::Circle {
+                r_out = r_out.min(self.fz.zones_clip().max(r_in));
+            }
+            if r_out > r_in {
+                let width = ((r_out - r_in) * root_k).max(1.);
+                root.draw(&Circle::new(
+                    center_of_circle,
+                    (r_in + r_out) / 2. * root_k,
+                    YELLOW.mix(0.6).stroke_width(width as u32),
+                ))
+                .unwrap();
+            }
+        }
+
+        // zones are bigger than the hole if they are shifted
+        if self.screen_mod == ScreenMod::Circle && (self.fz.zones_center() != 0. || exact) {
+            let width = root_size;
+            root.draw(&Circle::new(
+                (center as i32, center as i32),
+                x_otv * root_k + width / 2.,
+                BLACK.stroke_width(width as u32),
+            ))
+            .unwrap();
+        }
+
+        // draw screen, a transparent one is lighter
+        let screen_style = BLACK.mix(1. - self.fz.screen_amp as f64).filled();
+        if self.screen_mod == ScreenMod::Disk {
+            root.draw(&Circle::new(
+                (center as i32, center as i32),
+                x_otv.max(0.) * root_k,
+                screen_style,
             ))
             .unwrap();
-            last_r = r;
         }
-        // its wave too
-        root.draw(&Circle::new(
-            center_of_circle,
-            last_r * root_k,
-            ShapeStyle::from(&RED).filled(),
-        ))
-        .unwrap();
-
-        // draw screen
         match self.fz.rezhim {
             Screens::One => {
                 if self.screen_mod == ScreenMod::Rectangle {
-                    let x1 = center + self.fz.x_otv * root_k;
+                    let x1 = center + x_otv * root_k;
                     let x2 = (center + root_k * (MAX_X + 1.)) as i32;
                     root.draw(&Rectangle::new(
                         [(x1 as i32, 0), (x2, x2)],
-                        ShapeStyle::from(BLACK.filled()),
+                        screen_style,
                     ))
                     .unwrap();
                 }
             }
             Screens::Two => {
-                let p1 = self.fz.x_otv * root_k;
+                let p1 = x_otv * root_k;
                 let p2 = (center + root_k * (MAX_X + 1.)) as i32;
 
                 let x2 = center - p1;
                 root.draw(&Rectangle::new(
                     [(0, 0), (x2 as i32, p2)],
-                    ShapeStyle::from(BLACK.filled()),
+                    screen_style,
                 ))
                 .unwrap();
 
                 let x1 = center + p1;
                 root.draw(&Rectangle::new(
                     [(x1 as i32 + 2, 0), (p2, p2)],
-                    ShapeStyle::from(BLACK.filled()),
+                    screen_style,
                 ))
                 .unwrap();
             }
+            Screens::Intervals | Screens::Grating => {
+                let x_max = MAX_X as f64 + 1.;
+                let to_px = |x: f64| (center + x.clamp(-x_max, x_max) as f32 * root_k) as i32;
+                let bottom = (center + root_k * (MAX_X + 1.)) as i32;
+                for (a, b) in self.fz.opaque_parts(x_max) {
+                    root.draw(&Rectangle::new(
+                        [(to_px(a), 0), (to_px(b), bottom)],
+                        BLACK.filled(),
+                    ))
+                    .unwrap();
+                }
+                // semi-transparent intervals are shaded
+                for o in self.fz.openings(self.fz.x_otv.cm()) {
+                    let amp = o.t.norm().min(1.);
+                    if amp < 1. {
+                        root.draw(&Rectangle::new(
+                            [(to_px(o.a), 0), (to_px(o.b), bottom)],
+                            BLACK.mix(1. - amp).filled(),
+                        ))
+                        .unwrap();
+                    }
+                }
+            }
+        }
+
+        if lod.is_some() {
+            let font = ("sans-serif", 13).into_font().color(&WHITE);
+            let y = root_size as i32 - 18;
+            let text = format!("зоны тоньше {MIN_ZONE_PX} пикс. показаны плотностью");
+            root.draw(&Text::new(text, (4, y), font)).unwrap();
+        }
+
+        // outline of the screen over the distorted zones
+        if exact {
+            let outline = WHITE.stroke_width(2);
+            match self.screen_mod {
+                ScreenMod::Circle | ScreenMod::Disk => {
+                    let r = x_otv.max(0.) * root_k;
+                    root.draw(&Circle::new((center as i32, center as i32), r, outline)).unwrap();
+                }
+                ScreenMod::Rectangle => {
+                    let edges = self.fz.openings(self.fz.x_otv.cm());
+                    for x in edges.iter().flat_map(|o| [o.a, o.b]).filter(|x| x.abs() <= MAX_X as f64) {
+                        let x = to_px((x as f32, 0.)).0;
+                        root.draw(&PathElement::new(vec![(x, 0), (x, root_size as i32)], outline))
+                            .unwrap();
+                    }
+                }
+            }
         }
 
         root.present().unwrap();
@@ -625,37 +1065,43 @@ impl MainApp {
     fn draw_bottom_plot2(
         &mut self,
         ui: &mut Ui,
+        kind: BottomPlot,
         line: Vec<[f64; 2]>,
         red_point: [f64; 2],
+        model_line: Option<Vec<[f64; 2]>>,
         stud_points: Option<Vec<(f64, f64)>>,
     ) {
         // name of y axis
-        let nm = match stud_points {
-            Some(_) => "|F|",
-            None => "φ",
+        let nm = match (kind, self.is_diff()) {
+            (BottomPlot::Abs, false) => "|F|",
+            (BottomPlot::Arg, false) => "φ",
+            (BottomPlot::Abs, true) => "Δ|F|",
+            (BottomPlot::Arg, true) => "Δφ",
         };
 
-        let k = self.fz.k();
+        let (bottom, top) = self.fz.axis_names();
+        let (second, second_fmt) = (self.fz.second_axis(), self.fz.second_axis());
         // mess when hover on plot near mouse
-        let label_fmt = |_s: &str, val: &PlotPoint| {
-            format!("u:  {:.3}\nx:  {:.3}\n{nm}: {:.3}", val.x, val.x / k, val.y)
+        let label_fmt = move |_s: &str, val: &PlotPoint| {
+            format!("{bottom}:  {:.3}\n{top}:  {:.3}\n{nm}: {:.3}", val.x, second(val.x), val.y)
         };
 
-        let x_formatter =
-            |mark: GridMark, _range: &RangeInclusive<f64>| format!("{:.2}", mark.value / k);
+        let x_formatter = move |mark: GridMark, _range: &RangeInclusive<f64>| {
+            format!("{:.2}", second_fmt(mark.value))
+        };
 
         let x_axis = vec![
-            AxisHints::new_x().label("u"),
+            AxisHints::new_x().label(bottom),
             AxisHints::new_x()
-                .label("x")
+                .label(top)
                 .formatter(x_formatter)
                 .placement(egui_plot::VPlacement::Top),
         ];
 
         let sz = ui.available_size();
-        let x = match stud_points {
-            Some(_) => 0.,
-            None => sz.x,
+        let x = match kind {
+            BottomPlot::Abs => 0.,
+            BottomPlot::Arg => sz.x,
         };
         let rect = Rect::from_min_size(Pos2::new(x, sz.x), sz);
 
@@ -673,15 +1119,14 @@ impl MainApp {
             // if !response.hovered() {
             //     return None;
             // }
-            let scroll = i.events.iter().find_map(|e| match e {
+            i.events.iter().find_map(|e| match e {
                 Event::MouseWheel {
                     unit: _,
                     delta,
                     modifiers: _,
                 } => Some(*delta),
                 _ => None,
-            });
-            scroll
+            })
         });
 
         Plot::new("bottom_plot".to_owned() + nm)
@@ -706,6 +1151,70 @@ impl MainApp {
                 }
                 plot_ui.line(Line::new("u", line).color(Color32::BLUE));
 
+                // what the receiving antenna of finite width gets
+                if kind == BottomPlot::Abs && !self.is_diff() && !self.fz.difs_aperture.is_empty() {
+                    let line: Vec<[f64; 2]> = self.fz.difs_aperture.iter().map(|d| d.p_norm()).collect();
+                    plot_ui.line(Line::new("антенна", line).color(COLOR_APERTURE_EGUI));
+                }
+                // power averaged over the spectrum
+                if kind == BottomPlot::Abs && !self.is_diff() && !self.fz.difs_spectrum.is_empty() {
+                    let line: Vec<[f64; 2]> = self.fz.difs_spectrum.iter().map(|d| d.p_norm()).collect();
+                    plot_ui.line(Line::new("спектр", line).color(COLOR_SPECTRUM_EGUI));
+                }
+                if let Some(model_line) = model_line {
+                    plot_ui.line(Line::new(self.fz.model.name(), model_line).color(COLOR_MODEL_EGUI));
+                }
+
+                // extrema and other points of the Fresnel curve
+                if self.mark_features && !self.is_diff() {
+                    let marks: &[(FeatureKind, MarkerShape)] = match kind {
+                        BottomPlot::Abs => &[
+                            (FeatureKind::Max, MarkerShape::Up),
+                            (FeatureKind::Min, MarkerShape::Down),
+                            (FeatureKind::Zero, MarkerShape::Circle),
+                            (FeatureKind::Shadow, MarkerShape::Diamond),
+                        ],
+                        // the phase of a zero is undefined
+                        BottomPlot::Arg => &[(FeatureKind::Wrap, MarkerShape::Cross)],
+                    };
+                    let value = |f: &Complex64| match kind {
+                        BottomPlot::Abs => f.norm(),
+                        BottomPlot::Arg => f.arg(),
+                    };
+                    let shown: Vec<_> = self
+                        .fz
+                        .features
+                        .iter()
+                        .filter(|f| marks.iter().any(|m| m.0 == f.kind))
+                        .collect();
+
+                    for &(feature, shape) in marks {
+                        let points: Vec<[f64; 2]> = shown
+                            .iter()
+                            .filter(|f| f.kind == feature)
+                            .map(|f| [f.p, value(&f.field)])
+                            .collect();
+                        let points = Points::new(feature.name(), points)
+                            .radius(4.)
+                            .shape(shape)
+                            .color(COLOR_FEATURES_EGUI);
+                        plot_ui.points(points);
+                    }
+                    if shown.len() <= MAX_FEATURE_LABELS {
+                        for f in shown {
+                            let (y, text) = (value(&f.field), format!("{:.3}", value(&f.field)));
+                            let anchor = match f.kind {
+                                FeatureKind::Min | FeatureKind::Zero => egui::Align2::CENTER_TOP,
+                                _ => egui::Align2::CENTER_BOTTOM,
+                            };
+                            let text = egui_plot::Text::new(f.kind.name(), PlotPoint::new(f.p, y), text)
+                                .anchor(anchor)
+                                .color(COLOR_FEATURES_EGUI);
+                            plot_ui.text(text);
+                        }
+                    }
+                }
+
                 let points = Points::new("red_point".to_owned() + nm, vec![red_point])
                     .filled(true)
                     .radius(5.)
@@ -720,8 +1229,8 @@ impl MainApp {
                             if x == 0. && i == 0. {
                                 return None;
                             }
-                            let u = x * self.fz.k();
-                            Some(self.fz.get_point_norm(u))
+                            let u = self.fz.x_to_p(x);
+                            Some(self.fz.get_point_norm_received(u))
                         })
                         .collect::<Vec<_>>();
                     let tmp = Points::new("cross", points_cross)
@@ -736,19 +1245,846 @@ impl MainApp {
                             if x == 0. && i == 0. {
                                 return None;
                             }
-                            let u = x * self.fz.k();
+                            let u = self.fz.x_to_p(x);
                             Some([u, i / *self.fz.get_max_i()])
                         })
                         .collect::<Vec<_>>();
 
                     plot_ui.line(Line::new("I/Imax", line).color(Color32::ORANGE)); // students points
                 }
+            });
+    }
+
+    // is difference plot shown instead of curves
+    #[inline]
+    fn is_diff(&self) -> bool {
+        self.show_diff && self.fz.model != Model::Fresnel
+    }
+
+    fn model_ui(&mut self, ui: &mut Ui) {
+        let fz = &mut self.fz;
+        ui.horizontal(|ui| {
+            ui.label("модель:");
+
+            let last = fz.model;
+            egui::ComboBox::from_id_salt("model")
+                .selected_text(fz.model.name())
+                .show_ui(ui, |ui| {
+                    for model in Model::ALL {
+                        if model.supports(fz.rezhim, fz.scan, fz.screen_amp) {
+                            ui.selectable_value(&mut fz.model, model, model.name());
+                        }
+                    }
+                });
+            if fz.model != last {
+                fz.rebuild_integrals();
+            }
+        });
+
+        if fz.model != Model::Fresnel {
+            ui.checkbox(&mut self.show_diff, "разность с Френелем")
+                .on_hover_text("модель минус интегралы Френеля");
+        }
+
+        if fz.model == Model::Kirchhoff {
+            Self::beam_ui(fz, self.length_unit, ui);
+        } else if fz.scan == Scan::Receiver {
+            // only Kirchhoff sees the pattern, and it is not computed for a moved receiver
+            ui.add_enabled_ui(false, |ui| Self::beam_ui(fz, self.length_unit, ui));
+            ui.label("ДН источника учитывается только моделью Кирхгофа, когда двигается кромка");
+        }
+
+        Self::aperture_ui(fz, self.length_unit, ui);
+        Self::spectrum_ui(fz, self.freq_unit, ui);
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.pulse.show, "импульс")
+                .on_hover_text("форма импульса за экраном");
+            ui.checkbox(&mut self.map.show, "поле x–z")
+                .on_hover_text("карта поля за экраном по ширине и расстоянию");
+            ui.checkbox(&mut self.zone_plate.show, "зонная пластинка")
+                .on_hover_text("пластинка с фокусом на расстоянии L2");
+            ui.checkbox(&mut self.show_phasors, "вклады зон")
+                .on_hover_text("сумма векторов зон Френеля с картинки");
+            ui.checkbox(&mut self.show_features, "особые точки")
+                .on_hover_text("максимумы, минимумы, граница тени и скачки фазы");
+            ui.checkbox(&mut self.fringes.show, "λ по полосам")
+                .on_hover_text("длина волны (или расстояние) по экстремумам из таблицы");
+        });
+    }
+
+    // |F| or phase over the plane of propagation, the bottom plots are its cut at z = L2
+    fn map_window(&mut self, ui: &mut Ui) {
+        const NX: usize = 200;
+        const NZ: usize = 150;
+        const MAX_F: f64 = 1.5;
+
+        let mp = &mut self.map;
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut mp.phase, false, "|F|");
+            ui.radio_value(&mut mp.phase, true, "φ");
+            ui.label("z до:");
+            ui.add(length_drag(&mut mp.z_max, self.length_unit, 5.0..=200., 0.5));
+        });
+
+        let x_max = MAX_X as f64;
+        let stamp = (self.fz.stamp, mp.phase, mp.z_max);
+        // the map is made by the worker, the old picture is shown until then
+        if mp.asked != Some(stamp) {
+            let (fz, z_max) = (self.fz.clone(), mp.z_max.cm());
+            mp.worker.submit(move |job| {
+                let xs: Vec<f64> = (0..NX)
+                    .map(|i| -x_max + 2. * x_max * (i as f64 + 0.5) / NX as f64)
+                    .collect();
+                // top row of the picture is the farthest
+                let zs: Vec<f64> = (0..NZ)
+                    .map(|i| z_max * (1. - (i as f64 + 0.5) / NZ as f64))
+                    .collect();
+                fz.field_map(&xs, &zs, job)
+            });
+            mp.asked = Some(stamp);
+        }
+        if let Some(map) = mp.worker.poll() {
+            let pixels = map
+                .into_iter()
+                .map(|(abs, phi)| {
+                    let (r, g, b) = if mp.phase {
+                        HSLColor((phi / (2. * std::f64::consts::PI)).rem_euclid(1.), 0.9, 0.5).rgb()
+                    } else {
+                        ViridisRGB.get_color((abs / MAX_F).min(1.) as f32).rgb()
+                    };
+                    Color32::from_rgb(r, g, b)
+                })
+                .collect();
+            let image = egui::ColorImage::new([NX, NZ], pixels);
+            mp.texture = Some(ui.ctx().load_texture("field_map", image, egui::TextureOptions::LINEAR));
+        }
+        if mp.worker.busy() {
+            ui.add(egui::ProgressBar::new(mp.worker.progress()).text("расчёт карты"));
+            ui.ctx().request_repaint();
+        } else if mp.worker.failed() {
+            ui.colored_label(COLOR_RED_POINT_EGUI, "ошибка при расчёте карты");
+        }
+
+        let z_max = mp.z_max.cm();
+        let Some(texture) = mp.texture.as_ref().map(|t| t.id()) else {
+            return;
+        };
+        // the picture is in cm, the plot is in the unit of the panel
+        let unit = self.length_unit;
+        let k = Length::from_cm(1.).in_unit(unit);
+        let screens: Vec<[[f64; 2]; 2]> = self
+            .fz
+            .opaque_parts(x_max)
+            .into_iter()
+            .map(|(a, b)| [[a * k, 0.], [b * k, 0.]])
+            .collect();
+        // intervals which change the wave but do not stop it
+        let partial: Vec<[[f64; 2]; 2]> = self
+            .fz
+            .openings(self.fz.x_otv.cm())
+            .into_iter()
+            .filter(|o| (o.t - 1.).norm() > 1e-6)
+            .map(|o| [[o.a.max(-x_max) * k, 0.], [o.b.min(x_max) * k, 0.]])
+            .collect();
+        let bounds = (
+            self.fz.shadow_boundaries(0.),
+            self.fz.shadow_boundaries(z_max),
+        );
+        let receiver = [self.fz.x_rec.in_unit(unit), self.fz.l2.in_unit(unit)];
+
+        Plot::new("field_map")
+            .x_axis_label(format!("x ({})", unit.name()))
+            .y_axis_label(format!("z ({})", unit.name()))
+            .data_aspect(1.)
+            .legend(egui_plot::Legend::default())
+            .show(ui, |plot_ui| {
+                plot_ui.image(PlotImage::new(
+                    "поле",
+                    texture,
+                    PlotPoint::new(0., z_max * k / 2.),
+                    Vec2::new(2. * MAX_X, z_max as f32) * k as f32,
+                ));
+                for screen in screens {
+                    plot_ui.line(Line::new("экран", screen.to_vec()).color(Color32::BLACK).width(4.));
+                }
+                for screen in partial {
+                    plot_ui.line(Line::new("полупрозрачный экран", screen.to_vec()).color(Color32::GRAY).width(4.));
+                }
+                for (x0, x1) in bounds.0.into_iter().zip(bounds.1) {
+                    plot_ui.line(
+                        Line::new("граница тени", vec![[x0 * k, 0.], [x1 * k, z_max * k]])
+                            .color(Color32::WHITE)
+                            .style(egui_plot::LineStyle::dashed_loose()),
+                    );
+                }
+                plot_ui.points(
+                    Points::new("приёмник", vec![receiver])
+                        .radius(5.)
+                        .color(COLOR_RED_POINT_EGUI),
+                );
+            });
+    }
+
+    // zones of a straight edge are strips
+    #[inline]
+    fn strip_zones(&self) -> bool {
+        self.linear_zones
+            && self.screen_mod == ScreenMod::Rectangle
+            && matches!(self.fz.rezhim, Screens::One | Screens::Two)
+    }
+
+    // the snapshot of parameters goes to the worker, a long job shows its progress
+    fn curves_job(&mut self, ctx: &egui::Context) {
+        const SHOW_AFTER: f64 = 0.3; // s, short jobs are not shown
+
+        if self.fz.take_rebuild() {
+            let fz = self.fz.clone();
+            self.curves.submit(move |job| fz.compute(job));
+        }
+        if !self.curves.busy() {
+            self.busy_since = None;
+            if self.curves.failed() {
+                egui::Area::new(egui::Id::new("curves_progress"))
+                    .anchor(egui::Align2::RIGHT_BOTTOM, [-10., -10.])
+                    .show(ctx, |ui| ui.colored_label(COLOR_RED_POINT_EGUI, "ошибка при расчёте кривых"));
+            }
+            return;
+        }
+
+        let now = ctx.input(|i| i.time);
+        let since = *self.busy_since.get_or_insert(now);
+        if now - since > SHOW_AFTER {
+            egui::Area::new(egui::Id::new("curves_progress"))
+                .anchor(egui::Align2::RIGHT_BOTTOM, [-10., -10.])
+                .show(ctx, |ui| {
+                    let bar = egui::ProgressBar::new(self.curves.progress()).text("расчёт кривых");
+                    ui.add(bar.desired_width(200.));
+                });
+        }
+        ctx.request_repaint();
+    }
+
+    // points of the Fresnel curve which are compared with the experiment
+    fn features_window(&mut self, ui: &mut Ui) {
+        ui.checkbox(&mut self.mark_features, "отметить на графиках");
+
+        let fz = &self.fz;
+        let counts: Vec<String> = FeatureKind::ALL
+            .iter()
+            .map(|kind| {
+                let n = fz.features.iter().filter(|f| f.kind == *kind).count();
+                format!("{}: {n}", kind.name())
+            })
+            .collect();
+        ui.label(counts.join(", "));
+        if fz.features.is_empty() {
+            return;
+        }
+
+        let (bottom, top) = fz.axis_names();
+        let second = fz.second_axis();
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("features").striped(true).show(ui, |ui| {
+                for name in ["", bottom, top, "|F|", "φ"] {
+                    ui.label(name);
+                }
+                ui.end_row();
+                for f in &fz.features {
+                    ui.label(f.kind.name());
+                    ui.label(format!("{:.3}", f.p));
+                    ui.label(format!("{:.3}", second(f.p)));
+                    ui.label(format!("{:.4}", f.field.norm()));
+                    ui.label(format!("{:.1}°", f.field.arg().to_degrees()));
+                    ui.end_row();
+                }
+            });
+        });
+    }
+
+    // the unknown from the extrema of the table of measurements
+    fn fringes_window(&mut self, ui: &mut Ui) {
+        let unknown = &mut self.fringes.unknown;
+        ui.horizontal(|ui| {
+            ui.label("найти:");
+            for u in Unknown::ALL {
+                ui.radio_value(unknown, u, u.name());
+            }
+        });
+        let unknown = *unknown;
+
+        let fit = match fit(&self.fz, unknown) {
+            Ok(fit) => fit,
+            Err(reason) => {
+                ui.label(reason);
+                return;
+            }
+        };
+
+        let unit = self.length_unit;
+        match fit.value {
+            Some((q, sigma)) => {
+                ui.horizontal(|ui| {
+                    ui.strong(format!(
+                        "{} = {:.3} ± {:.3} {}",
+                        unknown.name(),
+                        q.in_unit(unit),
+                        sigma.in_unit(unit),
+                        unit.name(),
+                    ));
+                    let now = unknown.value(&self.fz).in_unit(unit);
+                    ui.label(format!("(сейчас {now:.3})"));
+                    if ui.button("принять").on_hover_text("взять в параметры установки").clicked() {
+                        *unknown.value_mut(&mut self.fz) = q;
+                    }
+                });
+                if unknown == Unknown::Lambda {
+                    let (f, generator, fu) = (q.frequency(), self.fz.freq(), self.freq_unit);
+                    let sigma_f = f.in_unit(fu) * sigma.cm() / q.cm();
+                    let gap = (f.ghz() / generator.ghz() - 1.) * 100.;
+                    ui.label(format!(
+                        "f = {:.3} ± {:.3} {}, генератор {:.3} {} ({gap:+.1} %)",
+                        f.in_unit(fu),
+                        sigma_f,
+                        fu.name(),
+                        generator.in_unit(fu),
+                        fu.name(),
+                    ));
+                }
+            }
+            None => {
+                ui.label(format!("{} с таким шагом полос не найдено", unknown.name()));
+            }
+        }
+
+        // the fit is in cm, the window is in the unit of the panel
+        let x = |cm: f64| Length::from_cm(cm).in_unit(unit);
+        let (line, pairs) = (&fit.line, &fit.pairs);
+        ui.label(format!(
+            "x = {:.3} + {:.4}·u {}, экстремумов: {}",
+            x(line.a),
+            x(line.b),
+            unit.name(),
+            pairs.len()
+        ));
+        egui::Grid::new("fringes").striped(true).show(ui, |ui| {
+            let (measured, theory) = (format!("x изм. ({})", unit.name()), format!("x теор. ({})", unit.name()));
+            for name in ["", "u", &measured, &theory] {
+                ui.label(name);
+            }
+            ui.end_row();
+            for p in pairs {
+                ui.label(p.kind.name());
+                ui.label(format!("{:.3}", p.u));
+                ui.label(format!("{:.3}", x(p.x)));
+                ui.label(format!("{:.3}", x(p.x_theory)));
+                ui.end_row();
+            }
+        });
+
+        let (u_min, u_max) = pairs.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(a, b), p| (a.min(p.u), b.max(p.u)));
+        let measured: Vec<[f64; 2]> = pairs.iter().map(|p| [p.u, x(p.x)]).collect();
+        let theory: Vec<[f64; 2]> = pairs.iter().map(|p| [p.u, x(p.x_theory)]).collect();
+        let fitted = vec![[u_min, x(line.a + line.b * u_min)], [u_max, x(line.a + line.b * u_max)]];
+        Plot::new("fringes")
+            .x_axis_label("u")
+            .y_axis_label(format!("x ({})", unit.name()))
+            .legend(egui_plot::Legend::default())
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new("прямая", fitted).color(Color32::BLUE));
+                plot_ui.points(Points::new("измерено", measured).radius(3.).color(Color32::BLUE));
+                plot_ui.points(Points::new("теория", theory).radius(3.).shape(MarkerShape::Cross).color(Color32::GRAY));
+            });
+    }
+
+    // arrows of zones of the picture and the rest of the sum, they add up to the field
+    fn zone_arrows(&self) -> (Vec<Complex64>, Complex64) {
+        let fz = &self.fz;
+        let geometry = fz.geometry();
+        let (c, b, x) = (geometry.shadow(), geometry.b(), fz.x_otv.cm());
+        let count = |r: f64| ((r * r / b).ceil() as usize).clamp(1, MAX_PHASORS);
+        let in_picture = (MAX_X * SQRT_2) as f64 + c.abs();
+
+        let (arrows, field) = match self.screen_mod {
+            ScreenMod::Rectangle if self.strip_zones() => {
+                let k = geometry.k();
+                let n = ((k * (MAX_X as f64 + c.abs())).powi(2) / 2.).ceil() as usize;
+                let (re, im) = fz.screen_field(&geometry, x);
+                let arrows = strip_phasors(&fz.openings(x), c, k, n.clamp(1, MAX_PHASORS));
+                (arrows, Complex64::new(re, im) / 2f64.sqrt())
+            }
+            ScreenMod::Rectangle => {
+                let openings = fz.openings(x);
+                let (re, im) = fz.screen_field(&geometry, x);
+                let arrows = zone_phasors(&ZoneScreen::Strips(&openings), c, b, count(in_picture));
+                (arrows, Complex64::new(re, im) / 2f64.sqrt())
+            }
+            ScreenMod::Circle | ScreenMod::Disk => {
+                let (r, t) = (x.max(0.), fz.screen_t());
+                let disk = self.screen_mod == ScreenMod::Disk;
+                // behind the hole only the transparent screen is left
+                let n = match disk || fz.screen_amp > 0. {
+                    true => count(in_picture),
+                    false => count(r + c.abs()),
+                };
+                let arrows = zone_phasors(&ZoneScreen::Round { r, t, disk }, c, b, n);
+                let curve = vibration_curve(&geometry, r + c.abs() + b.sqrt());
+                let (from, to) = round_arrow(&curve, r, t, disk);
+                (arrows, to_plot_frame(to - from))
+            }
+        };
+        let rest = field - arrows.iter().sum::<Complex64>();
+        (arrows, rest)
+    }
+
+    // zones as a chain of arrows, odd ones are blue and even are red as in the picture
+    fn phasors_window(&mut self, ui: &mut Ui) {
+        let (arrows, rest) = self.zone_arrows();
+        let field = arrows.iter().sum::<Complex64>() + rest;
+        let hovered = self.hovered_zone.filter(|&n| n <= arrows.len());
+
+        ui.label(format!(
+            "зон: {}, |F| = {:.3}, φ = {:.1}°",
+            arrows.len(),
+            field.norm(),
+            field.arg().to_degrees()
+        ));
+        let text = match hovered {
+            Some(n) => {
+                let a = arrows[n - 1];
+                format!("зона {n}: |a| = {:.3}, φ = {:.1}°", a.norm(), a.arg().to_degrees())
+            }
+            None => "наведите мышь на зону на картинке".to_string(),
+        };
+        ui.label(text);
+
+        let mut tip = Complex64::new(0., 0.);
+        let (mut odd, mut even) = ((Vec::new(), Vec::new()), (Vec::new(), Vec::new()));
+        let mut selected = None;
+        for (i, &a) in arrows.iter().enumerate() {
+            let (from, to) = ([tip.re, tip.im], [tip.re + a.re, tip.im + a.im]);
+            let part = if i % 2 == 0 { &mut odd } else { &mut even };
+            part.0.push(from);
+            part.1.push(to);
+            if hovered == Some(i + 1) {
+                selected = Some((from, to));
+            }
+            tip += a;
+        }
+
+        Plot::new("zone_phasors")
+            .data_aspect(1.)
+            .x_axis_label("Re")
+            .y_axis_label("Im")
+            .show(ui, |plot_ui| {
+                plot_ui.arrows(Arrows::new("нечётные", odd.0, odd.1).color(Color32::BLUE));
+                plot_ui.arrows(Arrows::new("чётные", even.0, even.1).color(Color32::RED));
+                plot_ui.arrows(
+                    Arrows::new("остальные зоны", vec![[tip.re, tip.im]], vec![[field.re, field.im]])
+                        .color(Color32::GRAY),
+                );
+                plot_ui.arrows(
+                    Arrows::new("F", vec![[0., 0.]], vec![[field.re, field.im]])
+                        .color(COLOR_MODEL_EGUI),
+                );
+                if let Some((from, to)) = selected {
+                    plot_ui.arrows(
+                        Arrows::new("", vec![from], vec![to])
+                            .color(Color32::YELLOW)
+                            .highlight(true),
+                    );
+                }
+            });
+    }
+
+    // zone plate for the current λ and L1 with the focus at L2
+    fn zone_plate_window(&mut self, ui: &mut Ui) {
+        let zp = &mut self.zone_plate;
+        let fz = &self.fz;
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("zone_plate")
+                .selected_text(zp.plate.name())
+                .show_ui(ui, |ui| {
+                    for plate in Plate::ALL {
+                        ui.selectable_value(&mut zp.plate, plate, plate.name());
+                    }
+                });
+            ui.label("зон:");
+            ui.add(DragValue::new(&mut zp.zones).range(1..=200));
+            ui.checkbox(&mut zp.exact, "точные радиусы")
+                .on_hover_text("по точной разности хода nλ/2, иначе r = √(n·b)");
+        });
+
+        let l1 = (!fz.plane_wave).then_some(fz.l1.cm());
+        let (l2, lambda) = (fz.l2.cm(), fz.lambda.cm());
+        let radii = radii(l1, l2, lambda, zp.zones, zp.exact);
+        let focus = on_axis(&radii, zp.plate, l1, lambda, l2);
+        let (unit, k) = (self.length_unit, Length::from_cm(1.).in_unit(self.length_unit));
+        ui.label(format!(
+            "r1 = {:.2} {u}, внешний радиус {:.2} {u}, I/I0 в фокусе {focus:.1}",
+            radii[0] * k,
+            radii[radii.len() - 1] * k,
+            u = unit.name(),
+        ));
+
+        // drawing for the workshop
+        ui.horizontal(|ui| {
+            ui.label("файл:");
+            ui.text_edit_singleline(&mut zp.path);
+            for (name, ext) in [("SVG", "svg"), ("DXF", "dxf")] {
+                if ui.button(name).on_hover_text("чертёж 1:1 в мм").clicked() {
+                    let path = std::path::Path::new(zp.path.trim()).with_extension(ext);
+                    let text = match ext {
+                        "svg" => svg(&radii, zp.plate),
+                        _ => dxf(&radii),
+                    };
+                    zp.message = Some(match std::fs::write(&path, text) {
+                        Ok(()) => format!("сохранено: {}", path.display()),
+                        Err(err) => format!("не сохранено: {err}"),
+                    });
+                }
+            }
+        });
+        if let Some(message) = &zp.message {
+            ui.label(message);
+        }
+
+        ui.horizontal(|ui| {
+            // the plate from the last zone to the center
+            let size = 180.;
+            let (rect, _) = ui.allocate_exact_size(Vec2::splat(size), egui::Sense::hover());
+            let edge = radii[radii.len() - 1] * 1.1;
+            let scale = size / 2. / edge as f32;
+            let painter = ui.painter();
+            painter.circle_filled(rect.center(), size / 2., Color32::BLACK);
+            for (i, &r) in radii.iter().enumerate().rev() {
+                let t = zp.plate.t(i + 1);
+                let color = if t == 0. {
+                    Color32::BLACK
+                } else if t < 0. {
+                    Color32::GRAY
+                } else {
+                    Color32::WHITE
+                };
+                painter.circle_filled(rect.center(), r as f32 * scale, color);
+            }
+
+            // higher foci are at L2/3, L2/5... for a plane wave
+            const N: usize = 600;
+            let line: Vec<[f64; 2]> = (1..=N)
+                .map(|i| {
+                    let z = 3. * l2 * i as f64 / N as f64;
+                    [z * k, on_axis(&radii, zp.plate, l1, lambda, z)]
+                })
+                .collect();
+            Plot::new("zone_plate_axis")
+                .x_axis_label(format!("z ({})", unit.name()))
+                .y_axis_label("I/I0 на оси")
+                .show(ui, |plot_ui| {
+                    plot_ui.line(Line::new("I/I0", line).color(Color32::BLUE));
+                    plot_ui.vline(egui_plot::VLine::new("L2", l2 * k).color(COLOR_RED_POINT_EGUI));
+                });
+        });
+    }
+
+    // time-domain waveform for the current position, the edge can be moved by itself
+    fn pulse_window(&mut self, ui: &mut Ui) {
+        let pp = &mut self.pulse;
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("pulse")
+                .selected_text(pp.pulse.name())
+                .show_ui(ui, |ui| {
+                    for pulse in Pulse::ALL {
+                        ui.selectable_value(&mut pp.pulse, pulse, pulse.name());
+                    }
+                });
+            if pp.pulse == Pulse::Gauss {
+                ui.label("полоса:")
+                    .on_hover_text("ширина спектра по уровню 0.5, центр - частота f");
+                ui.add(freq_drag(&mut pp.width, self.freq_unit, 0.5..=20., 0.05));
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let name = if pp.animate { "⏸" } else { "▶" };
+            if ui.button(name).on_hover_text("двигать кромку (или приёмник)").clicked() {
+                pp.animate = !pp.animate;
+            }
+            let unit = self.length_unit;
+            ui.add(length_drag(&mut pp.speed, unit, 0.1..=20., 0.1).suffix(format!("{}/с", unit.name())));
+        });
+
+        if pp.animate {
+            let step = pp.speed.cm() * ui.input(|i| i.stable_dt).min(0.1) as f64;
+            let fz = &mut self.fz;
+            let start = fz.get_start();
+            let (x, start) = match fz.scan {
+                Scan::Edge => (&mut fz.x_otv, start),
+                Scan::Receiver => (&mut fz.x_rec, -MAX_X),
+            };
+            *x = Length::from_cm(x.cm() + step);
+            if x.cm() > MAX_X as f64 {
+                *x = Length::from_cm(start as f64);
+            }
+            ui.ctx().request_repaint();
+        }
+
+        let (free, behind) = waveforms(
+            self.pulse.pulse,
+            self.fz.freq().ghz(),
+            self.pulse.width.ghz(),
+            |lambda| self.fz.current_factor(lambda),
+        );
+        Plot::new("pulse")
+            .x_axis_label("t (нс)")
+            .legend(egui_plot::Legend::default())
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new("без экранов", free).color(Color32::GRAY));
+                plot_ui.line(Line::new("за экраном", behind).color(Color32::BLUE));
+            });
+    }
+
+    // several frequencies at once, their powers are summed
+    fn spectrum_ui(fz: &mut Difr, unit: FreqUnit, ui: &mut Ui) {
+        let last = fz.spectrum.clone();
+        ui.horizontal(|ui| {
+            ui.label("спектр:");
+
+            let name = match fz.spectrum {
+                Spectrum::Mono => "одна частота",
+                Spectrum::Band { .. } => "полоса",
+                Spectrum::Lines(_) => "линии",
+            };
+            egui::ComboBox::from_id_salt("spectrum")
+                .selected_text(name)
+                .show_ui(ui, |ui| {
+                    if ui.selectable_label(fz.spectrum == Spectrum::Mono, "одна частота").clicked() {
+                        fz.spectrum = Spectrum::Mono;
+                    }
+                    let band = matches!(fz.spectrum, Spectrum::Band { .. });
+                    if ui.selectable_label(band, "полоса").clicked() && !band {
+                        fz.spectrum = Spectrum::Band { width: Frequency::from_ghz(1.) };
+                    }
+                    let lines = matches!(fz.spectrum, Spectrum::Lines(_));
+                    if ui.selectable_label(lines, "линии").clicked() && !lines {
+                        fz.spectrum = Spectrum::Lines(vec![(fz.freq(), 1.)]);
+                    }
+                })
+                .response
+                .on_hover_text("|F| = √<|F|²> по спектру, центр полосы - частота f");
+
+            if let Spectrum::Band { width } = &mut fz.spectrum {
+                ui.label("Δf:");
+                ui.add(freq_drag(width, unit, 0.01..=10., 0.01));
+            }
+        });
+
+        let freq = fz.freq();
+        if let Spectrum::Lines(lines) = &mut fz.spectrum {
+            let mut del = None;
+            for (n, (f, p)) in lines.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label("f:");
+                    ui.add(freq_drag(f, unit, 0.1..=100., 0.01));
+                    ui.label("P:");
+                    ui.add(DragValue::new(p).range(0.0..=1.).speed(0.01))
+                        .on_hover_text("относительная мощность линии");
+                    if ui.button("✖").clicked() {
+                        del = Some(n);
+                    }
+                });
+            }
+            if let Some(n) = del {
+                lines.remove(n);
+            }
+            if lines.len() < 10 && ui.button("+ линия").clicked() {
+                lines.push((freq, 1.));
+            }
+        }
+
+        if fz.spectrum != last {
+            fz.rebuild_integrals();
+        }
+    }
+
+    // open intervals of the screen with their transmissions
+    fn intervals_ui(fz: &mut Difr, unit: LengthUnit, ui: &mut Ui) {
+        let mut remove = None;
+        egui::Grid::new("intervals").striped(true).show(ui, |ui| {
+            ui.label("от");
+            ui.label("до");
+            ui.label("|t|").on_hover_text("модуль пропускания, 0 - непрозрачно");
+            ui.label("φ").on_hover_text("сдвиг фазы в интервале");
+            ui.end_row();
+
+            for (i, interval) in fz.intervals.iter_mut().enumerate() {
+                let b = interval.b;
+                interval_end_ui(ui, &mut interval.a, unit, f64::NEG_INFINITY, b);
+                let a = interval.a;
+                interval_end_ui(ui, &mut interval.b, unit, f64::INFINITY, a);
+                ui.add(DragValue::new(&mut interval.amp).range(0.0..=1.).speed(0.01));
+                ui.add(
+                    DragValue::new(&mut interval.phase)
+                        .range(-180.0..=180.)
+                        .suffix("°")
+                        .speed(1.),
+                );
+                if ui.button("🗑").clicked() {
+                    remove = Some(i);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(i) = remove {
+            fz.intervals.remove(i);
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button("➕ интервал").clicked() {
+                // next to the last finite end
+                let from = fz
+                    .intervals
+                    .iter()
+                    .flat_map(|i| [i.a.cm(), i.b.cm()])
+                    .filter(|e| e.is_finite())
+                    .fold(-MAX_X as f64 / 2., f64::max);
+                fz.intervals.push(Interval::new(Length::from_cm(from + 1.), Length::from_cm(from + 3.)));
+            }
+            egui::ComboBox::from_id_salt("interval_presets")
+                .selected_text("заготовки")
+                .show_ui(ui, |ui| {
+                    for preset in Preset::ALL {
+                        if ui.selectable_label(false, preset.name()).clicked() {
+                            fz.intervals = preset.intervals();
+                        }
+                    }
+                });
+        });
+    }
+
+    // N equal slits and how far the receiver is from the far zone
+    fn grating_ui(fz: &mut Difr, unit: LengthUnit, ui: &mut Ui) {
+        let g = &mut fz.grating;
+        ui.horizontal(|ui| {
+            ui.label("щелей:");
+            ui.add(DragValue::new(&mut g.n).range(1..=30));
+            ui.label("ширина:");
+            ui.add(length_drag(&mut g.width, unit, 0.1..=10., 0.05));
+            ui.label("период:");
+            let width = g.width.cm();
+            ui.add(length_drag(&mut g.period, unit, width..=20., 0.05));
+        });
+        if g.period < g.width {
+            g.period = g.width;
+        }
+
+        // Fresnel number of the whole grating, the far zone is N_F ≪ 1
+        let half = fz.grating.size() / 2.;
+        let n_f = half * half / fz.geometry().b();
+        let hint = "N_F = a²/λL, a - половина решётки; дальняя зона при N_F ≪ 1, \
+            с точечным источником L не больше L1, так что нужна плоская волна";
+        ui.label(format!("N_F = {n_f:.3}")).on_hover_text(hint);
+
+        // how the Fresnel pattern comes to the far field formula with L2
+        let current = [fz.l2.cm().log10(), 0.];
+        Plot::new("far_gap")
+            .height(100.)
+            .x_axis_label("lg L2")
+            .y_axis_label("макс. |ΔF|")
+            .allow_drag(false)
+            .allow_zoom(false)
+            .allow_scroll(false)
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new("Френель - дальняя зона", fz.far_gap.clone()).color(COLOR_MODEL_EGUI));
+                plot_ui.points(Points::new("L2", vec![current]).radius(4.).color(COLOR_RED_POINT_EGUI));
+            })
+            .response
+            .on_hover_text("наибольшая разница |F| Френеля и дальней зоны по положениям приёмника");
+    }
+
+    // receiving antenna smooths the oscillations of |F|
+    fn aperture_ui(fz: &mut Difr, unit: LengthUnit, ui: &mut Ui) {
+        let last = (fz.aperture, fz.aperture_width, fz.aperture_cos);
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut fz.aperture, "апертура приёмника")
+                .on_hover_text("|F| усредняется по ширине приёмной антенны");
+            if fz.aperture {
+                ui.add(length_drag(&mut fz.aperture_width, unit, 0.5..=20., 0.1));
+                ui.checkbox(&mut fz.aperture_cos, "cos")
+                    .on_hover_text("косинусное распределение поля по раскрыву (H-плоскость рупора)");
+            }
+        });
+        if (fz.aperture, fz.aperture_width, fz.aperture_cos) != last {
+            fz.rebuild_integrals();
+        }
+    }
+
+    // pattern of the source for numerical integration, Fresnel curves stay ideal
+    fn beam_ui(fz: &mut Difr, unit: LengthUnit, ui: &mut Ui) {
+        let last = fz.beam;
+        ui.add_enabled_ui(!fz.plane_wave, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("источник:");
+
+                let name = match fz.beam {
+                    Beam::Ideal => "точечный",
+                    Beam::Horn { .. } => "рупор cosⁿ",
+                    Beam::Gauss { .. } => "гауссов пучок",
+                };
+                egui::ComboBox::from_id_salt("beam")
+                    .selected_text(name)
+                    .show_ui(ui, |ui| {
+                        if ui.selectable_label(fz.beam == Beam::Ideal, "точечный").clicked() {
+                            fz.beam = Beam::Ideal;
+                        }
+                        let horn = matches!(fz.beam, Beam::Horn { .. });
+                        if ui.selectable_label(horn, "рупор cosⁿ").clicked() && !horn {
+                            fz.beam = Beam::Horn { width: 60. };
+                        }
+                        let gauss = matches!(fz.beam, Beam::Gauss { .. });
+                        if ui.selectable_label(gauss, "гауссов пучок").clicked() && !gauss {
+                            fz.beam = Beam::Gauss { waist: 3. };
+                        }
+                    });
             })
-            .response;
+            .response
+            .on_disabled_hover_text("только для точечного источника");
+
+            let lambda = fz.lambda.cm();
+            match &mut fz.beam {
+                Beam::Ideal => {}
+                Beam::Horn { width } => {
+                    ui.horizontal(|ui| {
+                        ui.label("ширина ДН:")
+                            .on_hover_text("полная ширина по уровню -3 дБ");
+                        ui.add(DragValue::new(width).range(5.0..=170.).suffix("°").speed(0.5));
+                        ui.label(format!("n = {:.1}", Beam::horn_n(*width)));
+                    });
+                }
+                Beam::Gauss { waist } => {
+                    ui.horizontal(|ui| {
+                        ui.label("перетяжка w₀:")
+                            .on_hover_text("радиус пучка в рупоре по уровню 1/e");
+                        // the beam is a part of the physics core, so it keeps cm
+                        let mut w0 = Length::from_cm(*waist);
+                        ui.add(length_drag(&mut w0, unit, 0.1..=20., 0.05));
+                        *waist = w0.cm();
+                        let theta = Beam::divergence(*waist, lambda).to_degrees();
+                        ui.label(format!("θ₀ = {theta:.1}°"))
+                            .on_hover_text("расходимость в дальней зоне");
+                    });
+                }
+            }
+        });
+
+        if fz.beam != last {
+            fz.rebuild_integrals();
+        }
     }
 
     fn table_ui(&mut self, ui: &mut Ui) {
         use egui_extras::{Column, TableBuilder};
+        let unit = self.length_unit;
         ui.vertical(|ui| {
             //over table
             let drag = DragValue::new(self.fz.get_max_i()).suffix("мА").speed(0.1);
@@ -773,7 +2109,11 @@ impl MainApp {
             table
                 .header(20.0, |mut header| { // header columns
                     header.col(|ui| {
-                        ui.strong("x (см)");
+                        let name = match self.fz.scan {
+                            Scan::Edge => "x",
+                            Scan::Receiver => "x пр.",
+                        };
+                        ui.strong(format!("{name} ({})", unit.name()));
                     });
                     header.col(|ui| {
                         ui.strong("u");
@@ -786,7 +2126,6 @@ impl MainApp {
                     });
                 })
                 .body(|mut body| { // main columns
-                    let k = self.fz.k();
                     let mut edited1 = false; // flag for sort student points
                     {
                         let mut points = self.fz.get_student_points();
@@ -798,10 +2137,9 @@ impl MainApp {
                         } else { //create if students points is empty
                             points.push((0., 0.));
                         }
-                        if let Some(i) = points.iter().position(|&point| point == (0., 0.)) {
-                            if i < points.len() - 1 { // del the 0,0 point if it not last
-                                points.remove(i);
-                            }
+                        if let Some(i) = points.iter().position(|&point| point == (0., 0.))
+                            && i < points.len() - 1 { // del the 0,0 point if it not last
+                            points.remove(i);
                         }
 
                         // draw main table
@@ -810,11 +2148,14 @@ impl MainApp {
                                 row.set_overline((row_index) % 5 == 0); // separator for every 5
 
                                 const ZERS: f64 = 1000.;
-                                let u = ((k * *x) * ZERS).round() / ZERS;
+                                let u = (self.fz.x_to_edge_u(*x) * ZERS).round() / ZERS;
                                 //x
                                 row.col(|ui| {
-                                    let tmp = cell_input(ui, x); // draw cell for input
-                                    if tmp && !edited1 {
+                                    // points are kept in cm
+                                    let mut shown = Length::from_cm(*x).in_unit(unit);
+                                    let tmp = cell_input(ui, &mut shown); // draw cell for input
+                                    if tmp {
+                                        *x = Length::from_unit(shown, unit).cm();
                                         edited1 = true;
                                     }
                                 });
@@ -832,7 +2173,7 @@ impl MainApp {
                                     // let f = (f * ZERS).round() / ZERS;
                                     // ui.label(f.to_string());
 
-                                    let f = self.fz.get_point_norm(u)[1];
+                                    let f = self.fz.get_point_norm_received(self.fz.x_to_p(*x))[1];
                                     ui.label(format!("{f:.3}"));
                                 });
                             });
@@ -848,6 +2189,11 @@ impl MainApp {
 
 impl eframe::App for MainApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // new curves from the worker, the old ones are drawn until they come
+        if let Some(curves) = self.curves.poll() {
+            self.fz.apply(curves);
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.with_layout(egui::Layout::left_to_right(egui::Align::LEFT), |ui| {
                 ui.with_layout(egui::Layout::top_down(egui::Align::TOP), |ui| {
@@ -858,7 +2204,10 @@ impl eframe::App for MainApp {
                         let size = Vec2::splat(available.x * 4. / COLS);
                         let inner_ui = &mut alloc_ui_block(ui, size);
 
-                        self.draw_3d(inner_ui, self.fz.difs_3d.clone());
+                        match self.screen_mod {
+                            ScreenMod::Rectangle => self.draw_3d(inner_ui, self.fz.difs_3d.clone()),
+                            ScreenMod::Circle | ScreenMod::Disk => self.draw_spiral(inner_ui),
+                        }
 
                         // draw params, vawe, ruprs
                         ui.with_layout(egui::Layout::top_down(egui::Align::TOP), |ui| {
@@ -876,19 +2225,39 @@ impl eframe::App for MainApp {
                     ui.with_layout(egui::Layout::left_to_right(egui::Align::LEFT), |ui| {
                         let size = Vec2::new(available.x * 4. / COLS, available.y * 2. / ROWS);
 
+                        // draw Δ|F|(u), Δphi(u)
+                        if let Some(point) = self.fz.get_current_point_diff()
+                            && self.is_diff()
+                        {
+                            let difs = self.fz.get_difs_diff();
+
+                            let inner_ui = &mut alloc_ui_block(ui, size);
+                            let line = difs.iter().map(|d| d.p_norm()).collect();
+                            self.draw_bottom_plot2(inner_ui, BottomPlot::Abs, line, point.p_norm(), None, None);
+
+                            let inner_ui = &mut alloc_ui_block(ui, size);
+                            let line = difs.iter().map(|d| d.p_arg()).collect();
+                            self.draw_bottom_plot2(inner_ui, BottomPlot::Arg, line, point.p_arg(), None, None);
+                            return;
+                        }
+
+                        let model = !self.fz.difs_model.is_empty();
+
                         // draw |F|(u)
                         let inner_ui = &mut alloc_ui_block(ui, size);
                         let point = self.fz.get_current_point_norm();
                         let line = self.fz.difs.iter().map(|d| d.p_norm()).collect();
+                        let model_line = model.then(|| self.fz.difs_model.iter().map(|d| d.p_norm()).collect());
                         let tmp = { self.fz.get_student_points().clone() };
-                        self.draw_bottom_plot2(inner_ui, line, point, Some(tmp));
+                        self.draw_bottom_plot2(inner_ui, BottomPlot::Abs, line, point, model_line, Some(tmp));
                         // self.draw_bottom_plot(inner_ui, line, point, Some(tmp));
 
                         // draw phi(u)
                         let inner_ui = &mut alloc_ui_block(ui, size);
                         let point = self.fz.get_current_point_arg();
                         let line = self.fz.difs.iter().map(|d| d.p_arg()).collect();
-                        self.draw_bottom_plot2(inner_ui, line, point, None);
+                        let model_line = model.then(|| self.fz.difs_model.iter().map(|d| d.p_arg()).collect());
+                        self.draw_bottom_plot2(inner_ui, BottomPlot::Arg, line, point, model_line, None);
                     });
                 });
 
@@ -901,6 +2270,8 @@ impl eframe::App for MainApp {
                     .size(Size::exact(body_text_size)) // for the source code link
                     .vertical(|mut strip| {
                         strip.cell(|ui| {
+                            self.model_ui(ui);
+                            ui.separator();
                             self.table_ui(ui);
                             // egui::ScrollArea::horizontal().show(ui, |ui| {
                             // });
@@ -909,6 +2280,50 @@ impl eframe::App for MainApp {
             });
         });
 
+        let mut show = self.pulse.show;
+        egui::Window::new("Импульс")
+            .open(&mut show)
+            .default_size([500., 300.])
+            .show(ctx, |ui| self.pulse_window(ui));
+        self.pulse.show = show;
+
+        let mut show = self.map.show;
+        egui::Window::new("Поле за экраном")
+            .open(&mut show)
+            .default_size([500., 400.])
+            .show(ctx, |ui| self.map_window(ui));
+        self.map.show = show;
+
+        let mut show = self.zone_plate.show;
+        egui::Window::new("Зонная пластинка")
+            .open(&mut show)
+            .default_size([600., 350.])
+            .show(ctx, |ui| self.zone_plate_window(ui));
+        self.zone_plate.show = show;
+
+        let mut show = self.show_phasors;
+        egui::Window::new("Вклады зон Френеля")
+            .open(&mut show)
+            .default_size([400., 400.])
+            .show(ctx, |ui| self.phasors_window(ui));
+        self.show_phasors = show;
+
+        let mut show = self.show_features;
+        egui::Window::new("Особые точки")
+            .open(&mut show)
+            .default_size([420., 300.])
+            .show(ctx, |ui| self.features_window(ui));
+        self.show_features = show;
+
+        let mut show = self.fringes.show;
+        egui::Window::new("Длина волны по полосам")
+            .open(&mut show)
+            .default_size([420., 400.])
+            .show(ctx, |ui| self.fringes_window(ui));
+        self.fringes.show = show;
+
+        self.curves_job(ctx);
+
         // ctx.show_viewport_immediate(
         //     egui::ViewportId("dop".into()),
         //     egui::ViewportBuilder::default().with_title("color"),
@@ -929,6 +2344,63 @@ pub fn add_param(ui: &mut Ui, name: &str, drg: DragValue) -> bool {
     tmp.changed()
 }
 
+#[inline]
+// length in the unit of the panel, range and speed are in cm
+pub fn length_drag(v: &mut Length, unit: LengthUnit, range: RangeInclusive<f64>, speed: f64) -> DragValue<'_> {
+    let k = Length::from_cm(1.).in_unit(unit);
+    DragValue::from_get_set(move |new| {
+        if let Some(new) = new {
+            *v = Length::from_unit(new, unit);
+        }
+        v.in_unit(unit)
+    })
+    .range(range.start() * k..=range.end() * k)
+    .suffix(unit.name())
+    .speed(speed * k)
+}
+
+#[inline]
+// frequency in the unit of the panel, range and speed are in ГГц
+pub fn freq_drag(v: &mut Frequency, unit: FreqUnit, range: RangeInclusive<f64>, speed: f64) -> DragValue<'_> {
+    let k = Frequency::from_ghz(1.).in_unit(unit);
+    DragValue::from_get_set(move |new| {
+        if let Some(new) = new {
+            *v = Frequency::from_unit(new, unit);
+        }
+        v.in_unit(unit)
+    })
+    .range(range.start() * k..=range.end() * k)
+    .suffix(unit.name())
+    .speed(speed * k)
+}
+
+#[inline]
+// end of an interval which can be moved to infinity, `other` is the other end
+fn interval_end_ui(ui: &mut Ui, v: &mut Length, unit: LengthUnit, infinity: f64, other: Length) {
+    let (max, other) = (MAX_X as f64, other.cm());
+    ui.horizontal(|ui| {
+        let infinite = v.cm().is_infinite();
+        if infinite {
+            ui.label(if infinity < 0. { "-∞" } else { "+∞" });
+        } else {
+            let range = if infinity < 0. {
+                -2. * max..=other.min(2. * max)
+            } else {
+                other.max(-2. * max)..=2. * max
+            };
+            ui.add(length_drag(v, unit, range, 0.1));
+        }
+        if ui.selectable_label(infinite, "∞").clicked() {
+            // back to a finite end, out of the window and beyond the other end
+            *v = Length::from_cm(match (infinite, infinity < 0.) {
+                (true, true) => (other - 2.).min(-max),
+                (true, false) => (other + 2.).max(max),
+                (false, _) => infinity,
+            });
+        }
+    });
+}
+
 #[inline]
 fn cell_input(ui: &mut Ui, v: &mut f64) -> bool {
     let copy = *v;
@@ -0,0 +1,353 @@
+use crate::windows::main_app::{freq_drag, length_drag};
+use crate::windows::math::units::{FreqUnit, Length, LengthUnit};
+use crate::windows::math::propagation::{Method, propagate};
+use eframe::emath::Align;
+use egui::load::{ImagePoll, SizeHint};
+use egui::{Color32, ColorImage, DragValue, Sense, TextureHandle, TextureOptions, Ui, Vec2};
+use egui_plot::{Line, Plot};
+use plotters::prelude::{Color, ColorMap, ViridisRGB};
+
+const N: usize = 128; // cells of the mask on a side
+const MAX_I: f64 = 2.; // top of the colour scale of I/I0
+
+/// mask of any shape instead of one or two screens
+pub struct MaskApp {
+    mask: Vec<f32>, // transmission of cells by rows, 1 is open
+    side: Length,   // size of the mask
+    l1: Length,
+    l2: Length,
+    lambda: Length,
+    plane_wave: bool,
+    method: Method,
+    length_unit: LengthUnit, // units of the window, the propagation is in cm anyway
+    freq_unit: FreqUnit,
+
+    // drawing
+    brush: f32, // radius in cells
+    draw_open: bool,
+
+    // loading of an image
+    path: String,
+    loading: bool,
+    error: Option<String>,
+
+    dirty: bool, // mask or parameters are changed
+    intensity: Vec<f64>,
+    view: Length, // size of the picture of intensity
+    mask_texture: Option<TextureHandle>,
+    result_texture: Option<TextureHandle>,
+}
+
+impl MaskApp {
+    // the same shapes as on the main window and a few more
+    fn preset(&mut self, open: impl Fn(f64, f64) -> bool) {
+        for i in 0..N {
+            for j in 0..N {
+                let (x, y) = (self.cell_x(j), self.cell_x(N - 1 - i));
+                self.mask[i * N + j] = if open(x, y) { 1. } else { 0. };
+            }
+        }
+        self.dirty = true;
+    }
+
+    // center of the cell in cm
+    #[inline]
+    fn cell_x(&self, j: usize) -> f64 {
+        ((j as f64 + 0.5) / N as f64 - 0.5) * self.side.cm()
+    }
+
+    fn presets_ui(&mut self, ui: &mut Ui) {
+        ui.horizontal_wrapped(|ui| {
+            let r = self.side.cm() / 8.;
+            if ui.button("круг").clicked() {
+                self.preset(|x, y| x.hypot(y) < r);
+            }
+            if ui.button("квадрат").clicked() {
+                self.preset(|x, y| x.abs() < r && y.abs() < r);
+            }
+            if ui.button("щель").clicked() {
+                self.preset(|x, _| x.abs() < r / 2.);
+            }
+            if ui.button("две щели").clicked() {
+                self.preset(|x, _| (x.abs() - r).abs() < r / 4.);
+            }
+            if ui.button("полуплоскость").clicked() {
+                self.preset(|x, _| x < 0.);
+            }
+            if ui.button("кольцо").clicked() {
+                self.preset(|x, y| (x.hypot(y) - r).abs() < r / 4.);
+            }
+            if ui.button("инвертировать").clicked() {
+                self.mask.iter_mut().for_each(|t| *t = 1. - *t);
+                self.dirty = true;
+            }
+            if ui.button("открыть всё").clicked() {
+                self.preset(|_, _| true);
+            }
+        });
+    }
+
+    fn load_ui(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("файл:");
+            ui.text_edit_singleline(&mut self.path)
+                .on_hover_text("PNG или SVG, белое - отверстие, чёрное - экран");
+            if ui.button("загрузить").clicked() {
+                ui.ctx().forget_image(&self.uri());
+                self.loading = true;
+                self.error = None;
+            }
+        });
+        if let Some(error) = &self.error {
+            ui.colored_label(Color32::RED, error);
+        }
+
+        if !self.loading {
+            return;
+        }
+        let size = SizeHint::Size {
+            width: N as u32,
+            height: N as u32,
+            maintain_aspect_ratio: false,
+        };
+        match ui.ctx().try_load_image(&self.uri(), size) {
+            Ok(ImagePoll::Ready { image }) => {
+                self.set_image(&image);
+                self.loading = false;
+            }
+            Ok(ImagePoll::Pending { .. }) => ui.ctx().request_repaint(),
+            Err(err) => {
+                self.error = Some(err.to_string());
+                self.loading = false;
+            }
+        }
+    }
+
+    #[inline]
+    fn uri(&self) -> String {
+        format!("file://{}", self.path.trim())
+    }
+
+    // nearest cell of the image, light is open
+    fn set_image(&mut self, image: &ColorImage) {
+        let [w, h] = image.size;
+        for i in 0..N {
+            for j in 0..N {
+                let c = image.pixels[(i * h / N) * w + j * w / N];
+                let light = (c.r() as u32 + c.g() as u32 + c.b() as u32) as f32 / (3. * 255.);
+                let alpha = c.a() as f32 / 255.;
+                self.mask[i * N + j] = if light * alpha > 0.5 { 1. } else { 0. };
+            }
+        }
+        self.dirty = true;
+    }
+
+    // the mask can be drawn by mouse: the left button draws, the right one erases
+    fn mask_ui(&mut self, ui: &mut Ui, size: f32) {
+        if self.dirty || self.mask_texture.is_none() {
+            let pixels = self
+                .mask
+                .iter()
+                .map(|&t| Color32::from_gray((t * 255.) as u8))
+                .collect();
+            let image = ColorImage::new([N, N], pixels);
+            self.mask_texture = Some(
+                ui.ctx()
+                    .load_texture("mask", image, TextureOptions::NEAREST),
+            );
+        }
+
+        let (rect, response) = ui.allocate_exact_size(Vec2::splat(size), Sense::click_and_drag());
+        let texture = self.mask_texture.as_ref().unwrap();
+        ui.painter().image(
+            texture.id(),
+            rect,
+            egui::Rect::from_min_max(egui::pos2(0., 0.), egui::pos2(1., 1.)),
+            Color32::WHITE,
+        );
+
+        let pressed = ui.input(|i| (i.pointer.primary_down(), i.pointer.secondary_down()));
+        if let Some(pos) = response.interact_pointer_pos()
+            && (pressed.0 || pressed.1)
+        {
+            let value = if pressed.0 == self.draw_open { 1. } else { 0. };
+            let cx = (pos.x - rect.min.x) / size * N as f32;
+            let cy = (pos.y - rect.min.y) / size * N as f32;
+            for i in 0..N {
+                for j in 0..N {
+                    let (dx, dy) = (j as f32 + 0.5 - cx, i as f32 + 0.5 - cy);
+                    if dx.hypot(dy) <= self.brush {
+                        self.mask[i * N + j] = value;
+                    }
+                }
+            }
+            self.dirty = true;
+        }
+    }
+
+    fn params_ui(&mut self, ui: &mut Ui) {
+        let (unit, freq_unit) = (self.length_unit, self.freq_unit);
+        let mut ch = false;
+        ui.horizontal(|ui| {
+            ui.label("единицы:");
+            for unit in LengthUnit::ALL {
+                ui.selectable_value(&mut self.length_unit, unit, unit.name());
+            }
+            ui.separator();
+            for unit in FreqUnit::ALL {
+                ui.selectable_value(&mut self.freq_unit, unit, unit.name());
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.add_enabled_ui(!self.plane_wave, |ui| {
+                ui.label("L1:");
+                ch |= ui.add(length_drag(&mut self.l1, unit, 1.0..=500., 0.1)).changed();
+            });
+            ch |= ui.checkbox(&mut self.plane_wave, "плоская волна").changed();
+        });
+        ui.horizontal(|ui| {
+            ui.label("L2:");
+            ch |= ui.add(length_drag(&mut self.l2, unit, 0.1..=500., 0.1)).changed();
+            ui.label("λ:");
+            ch |= ui.add(length_drag(&mut self.lambda, unit, 0.1..=5., 0.01)).changed();
+            // the frequency is not kept, λ is changed only by the user
+            ui.label("f:");
+            let mut freq = self.lambda.frequency();
+            if ui.add(freq_drag(&mut freq, freq_unit, 6.0..=300., 0.1)).changed() {
+                self.lambda = freq.wavelength();
+                ch = true;
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("размер маски:");
+            ch |= ui.add(length_drag(&mut self.side, unit, 5.0..=200., 0.1)).changed();
+
+            let last = self.method;
+            egui::ComboBox::from_id_salt("method")
+                .selected_text(self.method.name())
+                .show_ui(ui, |ui| {
+                    for method in Method::ALL {
+                        ui.selectable_value(&mut self.method, method, method.name());
+                    }
+                });
+            ch |= self.method != last;
+        });
+        ui.horizontal(|ui| {
+            ui.label("кисть:");
+            ui.add(DragValue::new(&mut self.brush).range(0.5..=20.).speed(0.1));
+            ui.radio_value(&mut self.draw_open, true, "отверстие");
+            ui.radio_value(&mut self.draw_open, false, "экран");
+        });
+        self.dirty |= ch;
+    }
+
+    fn result_ui(&mut self, ui: &mut Ui, size: f32) {
+        if self.dirty || self.result_texture.is_none() {
+            let l1 = (!self.plane_wave).then_some(self.l1.cm());
+            let (intensity, view) = propagate(
+                &self.mask,
+                N,
+                self.side.cm(),
+                self.lambda.cm(),
+                l1,
+                self.l2.cm(),
+                self.method,
+            );
+            (self.intensity, self.view) = (intensity, Length::from_cm(view));
+
+            let pixels = self
+                .intensity
+                .iter()
+                .map(|&i| {
+                    let (r, g, b) = ViridisRGB.get_color((i / MAX_I).min(1.) as f32).rgb();
+                    Color32::from_rgb(r, g, b)
+                })
+                .collect();
+            let image = ColorImage::new([N, N], pixels);
+            self.result_texture = Some(ui.ctx().load_texture(
+                "intensity",
+                image,
+                TextureOptions::LINEAR,
+            ));
+            self.dirty = false;
+        }
+
+        let unit = self.length_unit;
+        let texture = self.result_texture.as_ref().unwrap();
+        ui.image((texture.id(), Vec2::splat(size))).on_hover_text(format!(
+            "I/I0 от 0 до {MAX_I}, поле {:.1} {}",
+            self.view.in_unit(unit),
+            unit.name()
+        ));
+
+        // horizontal cut through the center
+        let row = N / 2;
+        let scale = self.view.in_unit(unit) / self.side.cm();
+        let line: Vec<[f64; 2]> = (0..N)
+            .map(|j| [self.cell_x(j) * scale, self.intensity[row * N + j]])
+            .collect();
+        Plot::new("mask_cut")
+            .height(ui.available_height())
+            .x_axis_label(format!("x ({})", unit.name()))
+            .y_axis_label("I/I0")
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new("I/I0", line).color(Color32::BLUE));
+            });
+    }
+}
+
+impl eframe::App for MaskApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            let size = (ui.available_height() * 0.6).min(ui.available_width() / 2.2);
+            ui.with_layout(egui::Layout::left_to_right(Align::TOP), |ui| {
+                ui.vertical(|ui| {
+                    ui.heading("маска");
+                    self.mask_ui(ui, size);
+                    self.presets_ui(ui);
+                    self.load_ui(ui);
+                    ui.separator();
+                    self.params_ui(ui);
+                });
+                ui.separator();
+                ui.vertical(|ui| {
+                    ui.heading(format!(
+                        "интенсивность на расстоянии L2 = {:.1} {}",
+                        self.l2.in_unit(self.length_unit),
+                        self.length_unit.name()
+                    ));
+                    self.result_ui(ui, size);
+                });
+            });
+        });
+    }
+}
+
+impl Default for MaskApp {
+    fn default() -> Self {
+        let mut app = Self {
+            mask: vec![0.; N * N],
+            side: Length::from_cm(40.),
+            l1: Length::from_cm(40.),
+            l2: Length::from_cm(40.),
+            lambda: Length::from_cm(3.),
+            plane_wave: false,
+            method: Method::AngularSpectrum,
+            length_unit: LengthUnit::default(),
+            freq_unit: FreqUnit::default(),
+            brush: 3.,
+            draw_open: true,
+            path: String::new(),
+            loading: false,
+            error: None,
+            dirty: true,
+            intensity: Vec::new(),
+            view: Length::from_cm(40.),
+            mask_texture: None,
+            result_texture: None,
+        };
+        let r = app.side.cm() / 8.;
+        app.preset(|x, y| x.hypot(y) < r);
+        app
+    }
+}
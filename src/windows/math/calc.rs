@@ -1,12 +1,14 @@
-const SPEED_OF_LIGHT: f32 = 29.9792458;
+use crate::windows::math::worker::Job;
+use num_complex::Complex64;
+use std::f64::consts::{PI, TAU};
 
-pub fn converter_freq(freq: f32) -> f32 {
-    //ГГц <-> см
-    SPEED_OF_LIGHT / freq
-}
+// adaptive sampling: the least first even grid, the deepest halving and the most points
+const START_PIECES: usize = 64;
+const MAX_DEPTH: u32 = 14;
+const MAX_POINTS: usize = 20_000;
 
 // elements of difraction-factor
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct DifrPoint {
     u: f64,
     abs: f64,
@@ -30,4 +32,137 @@ impl DifrPoint {
     pub fn p_norm(&self) -> [f64;2] {
         [self.u, self.abs]
     }
+
+    // difference of two models in the same u
+    #[inline]
+    pub fn sub(&self, other: &DifrPoint) -> Self {
+        Self::new(self.u, self.abs - other.abs, wrap_phase(self.phi - other.phi))
+    }
+}
+
+// linear interpolation of the curve in u, None outside of it
+pub fn interpolate(points: &[DifrPoint], u: f64) -> Option<DifrPoint> {
+    let i = points.partition_point(|p| p.u < u).max(1);
+    let (a, b) = (points.get(i - 1)?, points.get(i)?);
+    if u < a.u {
+        return None;
+    }
+
+    let t = (u - a.u) / (b.u - a.u);
+    let re = a.abs * a.phi.cos() * (1. - t) + b.abs * b.phi.cos() * t;
+    let im = a.abs * a.phi.sin() * (1. - t) + b.abs * b.phi.sin() * t;
+    Some(DifrPoint::new(u, re.hypot(im), im.atan2(re)))
+}
+
+// phase in (-π, π]
+#[inline]
+pub fn wrap_phase(phi: f64) -> f64 {
+    PI - (PI - phi).rem_euclid(TAU)
+}
+
+/// points (p, f(p)) over [a, b] such that the chord between neighbours is off the
+/// curve in the middle by less than tol, so dense where the curve bends and oscillates.
+/// The first grid of `pieces` must not miss whole oscillations. If the points do not fit
+/// in MAX_POINTS, tol is raised for the whole range, so the error stays the same everywhere.
+/// None if the job is cancelled
+pub fn adaptive_samples(
+    a: f64,
+    b: f64,
+    pieces: usize,
+    tol: f64,
+    job: &Job,
+    f: impl Fn(f64) -> Complex64,
+) -> Option<Vec<(f64, Complex64)>> {
+    let pieces = pieces.clamp(START_PIECES, MAX_POINTS / 2);
+    let grid: Vec<_> = (0..=pieces)
+        .map(|i| {
+            let p = a + (b - a) * i as f64 / pieces as f64;
+            (p, f(p))
+        })
+        .collect();
+
+    let mut tol = tol;
+    loop {
+        let mut out = vec![grid[0]];
+        let fits = grid
+            .windows(2)
+            .all(|w| job.alive() && refine(&f, w[0], w[1], tol, MAX_DEPTH, &mut out));
+        if !job.alive() {
+            return None;
+        }
+        if fits {
+            return Some(out);
+        }
+        // the deviation goes as h², so the number of points halves
+        tol *= 4.;
+    }
+}
+
+// the piece from left to right, left is already in out. false if out has overflowed
+fn refine(
+    f: &impl Fn(f64) -> Complex64,
+    left: (f64, Complex64),
+    right: (f64, Complex64),
+    tol: f64,
+    depth: u32,
+    out: &mut Vec<(f64, Complex64)>,
+) -> bool {
+    let p = (left.0 + right.0) / 2.;
+    let middle = (p, f(p));
+    // the deviation is h²·f''/8, so this is the test of curvature
+    let bent = (middle.1 - (left.1 + right.1) / 2.).norm() > tol;
+    if bent && depth > 0 {
+        if out.len() >= MAX_POINTS {
+            return false;
+        }
+        refine(f, left, middle, tol, depth - 1, out) && refine(f, middle, right, tol, depth - 1, out)
+    } else {
+        out.push(middle);
+        out.push(right);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chirp(u: f64) -> Complex64 {
+        Complex64::from_polar(1., PI / 2. * u * u)
+    }
+
+    // largest distance from the curve to the chords between the samples
+    fn chord_error(samples: &[(f64, Complex64)]) -> f64 {
+        samples
+            .windows(2)
+            .flat_map(|w| {
+                (1..8).map(move |i| {
+                    let t = i as f64 / 8.;
+                    let p = w[0].0 * (1. - t) + w[1].0 * t;
+                    (chirp(p) - (w[0].1 * (1. - t) + w[1].1 * t)).norm()
+                })
+            })
+            .fold(0., f64::max)
+    }
+
+    #[test]
+    fn chord_error_under_tol() {
+        let tol = 1e-3;
+        let samples = adaptive_samples(-10., 10., 64, tol, &Job::detached(), chirp).unwrap();
+        assert!(samples.len() < MAX_POINTS);
+        assert!(samples.windows(2).all(|w| w[0].0 < w[1].0));
+        // the test in the middle estimates the deviation up to the higher terms
+        assert!(chord_error(&samples) < 1.5 * tol, "{}", chord_error(&samples));
+    }
+
+    // over the budget both ends get the same tol, the right end is not left coarse
+    #[test]
+    fn budget_is_uniform() {
+        let samples = adaptive_samples(-60., 60., 64, 1e-9, &Job::detached(), chirp).unwrap();
+        assert!(samples.len() <= MAX_POINTS + 2);
+        let left = samples.iter().filter(|s| s.0 < 0.).count();
+        let right = samples.len() - left;
+        let ratio = left as f64 / right as f64;
+        assert!((0.9..1.1).contains(&ratio), "{left} {right}");
+    }
 }
@@ -0,0 +1,75 @@
+use crate::windows::math::geometry::Geometry;
+use num_complex::Complex64;
+use std::f64::consts::TAU;
+
+// Vibration curve of a round hole centered on the direct ray.
+//
+// The field in the receiver is the sum of thin rings, the ring between ρ and ρ + dρ
+// gives w·jk·e^(-jkΔ)·dΔ, Δ is the path difference via the ring and
+// w = (cosθ1 + cosθ2)/2 · (R1 + R2)/(r + s) is the obliquity and the fall of
+// amplitude, w = 1 at the center. With w = 1 the curve is a circle around 1,
+// the decay of w winds it into 1, the wave without screens.
+// A disk gives the rest of the sum, from its edge to 1, so the center of its
+// shadow is bright: the Poisson–Arago spot.
+
+const STEPS_PER_ZONE: f64 = 24.;
+
+// path difference via the ring and the weight of the ring
+#[inline]
+fn ring(rho: f64, r1: f64, r2: f64) -> (f64, f64) {
+    let s = rho.hypot(r2);
+    let (path, w) = if r1.is_infinite() {
+        (s - r2, (1. + r2 / s) / 2.)
+    } else {
+        let r = rho.hypot(r1);
+        (r - r1 + s - r2, (r1 / r + r2 / s) / 2. * (r1 + r2) / (r + s))
+    };
+    (path, w)
+}
+
+/// sum of rings from the center to ρ for ρ up to rho_max, pairs (ρ, sum)
+pub fn vibration_curve(geometry: &Geometry, rho_max: f64) -> Vec<(f64, Complex64)> {
+    let (r1, r2, _) = geometry.ray();
+    let k = TAU / geometry.lambda;
+
+    // Δ is almost ρ², so steps are even in ρ²
+    let zones = ring(rho_max, r1, r2).0 / (geometry.lambda / 2.);
+    let n = (zones * STEPS_PER_ZONE).ceil().max(100.) as usize;
+
+    let mut sum = Complex64::new(0., 0.);
+    let (mut last, mut last_w) = (Complex64::new(1., 0.), 1.);
+    let mut curve = Vec::with_capacity(n + 1);
+    curve.push((0., sum));
+    for i in 1..=n {
+        let rho = rho_max * (i as f64 / n as f64).sqrt();
+        let (path, w) = ring(rho, r1, r2);
+        let phasor = Complex64::from_polar(1., -k * path);
+        // exact for e^(-jkΔ), w is taken as the mean over the step
+        sum += (last - phasor) * ((w + last_w) / 2.);
+        curve.push((rho, sum));
+        (last, last_w) = (phasor, w);
+    }
+    curve
+}
+
+/// point of the curve at the radius rho
+pub fn curve_at(curve: &[(f64, Complex64)], rho: f64) -> Complex64 {
+    let i = curve.partition_point(|p| p.0 < rho);
+    match (i.checked_sub(1).and_then(|j| curve.get(j)), curve.get(i)) {
+        (Some(a), Some(b)) => a.1 + (b.1 - a.1) * ((rho - a.0) / (b.0 - a.0)),
+        (None, Some(b)) => b.1,
+        (Some(a), None) => a.1,
+        (None, None) => Complex64::new(0., 0.),
+    }
+}
+
+/// ends of the arrow of the hole (or disk) of radius a on the curve,
+/// the rest of the screen passes t of its part of the sum
+pub fn round_arrow(curve: &[(f64, Complex64)], a: f64, t: Complex64, disk: bool) -> (Complex64, Complex64) {
+    let z = curve_at(curve, a);
+    let one = Complex64::new(1., 0.);
+    match disk {
+        false => (Complex64::new(0., 0.), z + (one - z) * t),
+        true => (z - z * t, one),
+    }
+}
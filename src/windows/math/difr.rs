@@ -1,126 +1,640 @@
-use crate::windows::math::calc::{converter_freq, DifrPoint};
-use fresnel::fresnl;
+use crate::windows::math::calc::{adaptive_samples, interpolate, DifrPoint};
+use crate::windows::math::features::{bisect, extrema, phase_wraps, Feature, FeatureKind};
+use crate::windows::math::fresnel::{fresnel, fresnl, Precision};
+use crate::windows::math::geometry::{Beam, Geometry};
+use crate::windows::math::kirchhoff::kirchhoff_curve;
+use crate::windows::math::sommerfeld::{half_plane, Polarisation};
+use crate::windows::math::spectrum::Spectrum;
+use crate::windows::math::worker::Job;
+use crate::windows::math::units::{Frequency, Length};
+use crate::windows::math::transmission::{
+    far_field, fresnel_end, opaque_parts, openings_field, Grating, Interval, Opening, Preset,
+};
+use num_complex::Complex64;
 use std::cell::{RefCell, RefMut};
-use std::f64::consts::SQRT_2;
+use std::f64::consts::{PI, SQRT_2, TAU};
 
 pub const MAX_X: f32 = 20.;
+const APERTURE_N: usize = 21; // points over the receiving antenna
+const FAR_GAP_N: usize = 40; // distances L2 for the convergence to the far zone
+const ZONE_POINTS: usize = 120; // points of a boundary of an exact zone
+const TOLERANCE: f64 = 1e-3; // largest error of |F| between points of plots
+
+// point of the Cornu spiral in 3D: (u, C, S)
+pub type Point3 = (f64, f64, f64);
+
+// boundary of a zone on the screen, (x, y) in cm
+pub type ZoneOutline = Vec<(f32, f32)>;
 
 #[derive(PartialEq, Copy, Clone)]
 pub enum Screens {
     One,
     Two,
+    Intervals, // any list of intervals moved together by x_otv
+    Grating,   // N equal slits centered at x_otv
+}
+
+// what is moved during the experiment
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Scan {
+    Edge,     // the receiver is fixed, the edge is moved
+    Receiver, // the edge is fixed, the receiver is moved sideways
 }
 
+// model which is drawn next to the Fresnel integrals
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Model {
+    Fresnel,
+    SommerfeldTe,
+    SommerfeldTm,
+    Kirchhoff,
+    FarField,
+}
+
+impl Model {
+    pub const ALL: [Model; 5] = [
+        Model::Fresnel,
+        Model::SommerfeldTe,
+        Model::SommerfeldTm,
+        Model::Kirchhoff,
+        Model::FarField,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Model::Fresnel => "Френель",
+            Model::SommerfeldTe => "Зоммерфельд TE",
+            Model::SommerfeldTm => "Зоммерфельд TM",
+            Model::Kirchhoff => "Кирхгоф (численно)",
+            Model::FarField => "Фраунгофер (дальняя зона)",
+        }
+    }
+
+    // the exact solution is known only for a single perfectly conducting edge,
+    // Kirchhoff integral for every receiver position is too slow
+    pub fn supports(&self, rezhim: Screens, scan: Scan, screen_amp: f32) -> bool {
+        match self {
+            Model::Fresnel => true,
+            Model::Kirchhoff => scan == Scan::Edge,
+            Model::SommerfeldTe | Model::SommerfeldTm => rezhim == Screens::One && screen_amp == 0.,
+            Model::FarField => rezhim == Screens::Grating,
+        }
+    }
+}
+
+// what compute gives back, it is moved into Difr by apply
+pub struct Curves {
+    difs: Vec<DifrPoint>,
+    difs_model: Vec<DifrPoint>,
+    difs_aperture: Vec<DifrPoint>,
+    difs_spectrum: Vec<DifrPoint>,
+    difs_3d: Vec<Point3>,
+    far_gap: Vec<[f64; 2]>,
+    features: Vec<Feature>,
+    features_received: Vec<Feature>,
+}
+
+// a clone is the snapshot of parameters for the worker
+#[derive(Clone)]
 pub struct Difr {
-    pub x_otv: f32, // len between screen center and screen edge
-    pub l1: f32, // len between rupr and screen
-    pub l2: f32,
-    pub lambda: f32,
-    pub freq: f32,
+    pub x_otv: Length, // len between screen center and screen edge, shift for intervals
+    pub intervals: Vec<Interval>, // open parts of the screen in Intervals mode
+    pub grating: Grating,
+    // transmission of the screen itself in One and Two: dielectric sheets and absorbers
+    pub screen_amp: f32,
+    pub screen_phase: f32, // degrees
+    pub l1: Length, // len between rupr and screen
+    pub l2: Length,
+    pub lambda: Length, // the frequency is got from it
+    pub plane_wave: bool, // L1 → ∞
+    pub angle: f32, // incidence angle in degrees
+    pub x_rec: Length, // receiver offset from the axis
+    pub beam: Beam, // pattern of the source, only Kirchhoff model sees it
+    // receiving antenna of finite width
+    pub aperture: bool,
+    pub aperture_width: Length,
+    pub aperture_cos: bool, // cosine distribution over the antenna (H-plane of horn)
+    pub spectrum: Spectrum,
     pub rezhim: Screens,
+    pub scan: Scan,
+    pub model: Model,
 
     pub difs: Vec<DifrPoint>, // for abs and angle
+    pub difs_model: Vec<DifrPoint>, // the same for model, empty for Fresnel
+    pub difs_aperture: Vec<DifrPoint>, // averaged over the antenna, empty without it
+    pub difs_spectrum: Vec<DifrPoint>, // √<|F|²> over the spectrum, empty for Mono
     pub difs_3d: Vec<(f64, f64, f64)>, // for 3D
+    pub far_gap: Vec<[f64; 2]>, // lg L2 and the largest |F| of Fresnel minus far field, gratings only
+    pub features: Vec<Feature>, // extrema, shadow boundaries and phase wraps of difs, by p
+    pub features_received: Vec<Feature>, // extrema of what the antenna gets, empty for a point probe and Mono
+    pub stamp: u64, // number of rebuilds, pictures made from Difr compare it
+    rebuild: bool, // the curves are outdated and are asked from the worker
 
     // copies of values to track changes
-    x_otv_c: f32,
-    l1_c: f32,
-    l2_c: f32,
-    lambda_c: f32,
+    x_otv_c: Length,
+    intervals_c: Vec<Interval>,
+    grating_c: Grating,
+    screen_amp_c: f32,
+    screen_phase_c: f32,
+    l1_c: Length,
+    l2_c: Length,
+    lambda_c: Length,
+    plane_wave_c: bool,
+    angle_c: f32,
+    x_rec_c: Length,
 
     // students point for 1 and 2 screens
     student_points_1: RefCell<Vec<(f64, f64)>>,
     student_points_2: RefCell<Vec<(f64, f64)>>,
+    // the same when the receiver is moved
+    student_points_r1: RefCell<Vec<(f64, f64)>>,
+    student_points_r2: RefCell<Vec<(f64, f64)>>,
+    // and for intervals and gratings
+    student_points_n: RefCell<Vec<(f64, f64)>>,
+    student_points_rn: RefCell<Vec<(f64, f64)>>,
+    student_points_g: RefCell<Vec<(f64, f64)>>,
+    student_points_rg: RefCell<Vec<(f64, f64)>>,
     // hear max I
     max_i_1: f64,
     max_i_2: f64,
+    max_i_r1: f64,
+    max_i_r2: f64,
+    max_i_n: f64,
+    max_i_rn: f64,
+    max_i_g: f64,
+    max_i_rg: f64,
 }
 
 impl Difr {
-    // recalculate of diffractor-factor
+    // the curves are remade by the worker, see `compute`
     #[inline]
     pub fn rebuild_integrals(&mut self) {
-        const STEP: f64 = 40. / 1000.;
-        let k = self.k();
+        self.rebuild = true;
+    }
 
-        let us = 0..=(40. / STEP).ceil() as i32;
+    // true once after rebuild_integrals, then the snapshot goes to the worker
+    #[inline]
+    pub fn take_rebuild(&mut self) -> bool {
+        std::mem::take(&mut self.rebuild)
+    }
 
-        self.difs_3d = us
-            .clone()
-            .map(|x| {
-                let x = -MAX_X as f64 + (x as f64) * STEP;
-                let u = x * k;
+    // recalculate of diffractor-factor on a snapshot, None if the job is cancelled
+    pub fn compute(&self, job: &Job) -> Option<Curves> {
+        // ends of plots: u of the edge or x of the receiver
+        let (p_min, p_max) = match (self.scan, self.rezhim) {
+            (Scan::Edge, Screens::Two) => (self.x_to_u(0.), self.x_to_u(MAX_X as f64)),
+            (Scan::Edge, _) => (self.x_to_u(-MAX_X as f64), self.x_to_u(MAX_X as f64)),
+            (Scan::Receiver, _) => (-MAX_X as f64, MAX_X as f64),
+        };
 
-                let (c, s) = fresnl(u);
-                (u, c, s)
+        // part of the spiral which is passed by the ends, u is linear in p
+        let (u_min, u_max) = [p_min, p_max]
+            .iter()
+            .flat_map(|&p| {
+                let (geometry, x) = self.setup_at(p);
+                self.edge_us(&geometry, x)
             })
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), u| (lo.min(u), hi.max(u)));
+        let (u_min, u_max) = if u_min < u_max { (u_min, u_max) } else { (-1., 1.) };
+        // e^(jπu²/2) has the period 2/u, the first grid has two pieces on it
+        let pieces = ((u_max - u_min) * u_min.abs().max(u_max.abs())).ceil() as usize;
+
+        // points are dense where the field oscillates, other curves use the same ones
+        let samples = adaptive_samples(p_min, p_max, pieces, TOLERANCE, job, |p| {
+            let (re, im) = self.field_at(p);
+            Complex64::new(re, im) / SQRT_2
+        })?;
+        let ps: Vec<f64> = samples.iter().map(|s| s.0).collect();
+        job.report(0.2);
+
+        // the spiral is drawn in f32, in the order of fresnl
+        let difs_3d = adaptive_samples(u_min, u_max, pieces, TOLERANCE, job, |u| {
+            let (c, s) = fresnel(u, Precision::Single);
+            Complex64::new(s, c)
+        })?
+        .into_iter()
+        .map(|(u, f)| (u, f.re, f.im))
+        .collect();
+
+        let difs = samples
+            .iter()
+            .map(|&(p, f)| DifrPoint::new(p, f.norm(), f.arg()))
             .collect();
 
-        let points: Vec<(f64, f64, f64)> = match self.rezhim {
-            Screens::One => us
-                .map(|x| {
-                    let x = -MAX_X as f64 + (x as f64) * STEP;
-                    let u = x * k;
+        let field = |p: f64| {
+            let (re, im) = self.field_at(p);
+            Complex64::new(re, im) / SQRT_2
+        };
+        let mut features = extrema(&samples, field);
+        features.extend(phase_wraps(&samples, field));
+        features.extend(
+            self.shadow_ps(p_min, p_max)
+                .into_iter()
+                .map(|p| Feature::at(FeatureKind::Shadow, p, field)),
+        );
+        features.sort_by(|a, b| a.p.total_cmp(&b.p));
+
+        if !job.alive() {
+            return None;
+        }
+        job.report(0.3);
 
-                    let (c, s) = fresnl(u);
+        let difs_model = ps
+            .iter()
+            .zip(self.model_factors(&ps, job)?)
+            .map(|(&p, f)| DifrPoint::new(p, f.norm(), f.arg()))
+            .collect();
+
+        if !job.alive() {
+            return None;
+        }
+        job.report(0.6);
 
-                    let re = c + 0.5;
-                    let im = s + 0.5;
-                    let abs = re.hypot(im);
-                    let phi = im.atan2(re);
-                    (u, abs, phi)
+        let difs_aperture = match self.aperture {
+            true => ps
+                .iter()
+                .map(|&p| {
+                    let (geometry, x) = self.setup_at(p);
+                    let (re, im) = self.field_averaged(&geometry, x);
+                    DifrPoint::new(p, re.hypot(im) / SQRT_2, im.atan2(re))
                 })
                 .collect(),
-            Screens::Two => us
-                .map(|x| {
-                    let x = (x as f64) * STEP / 2.;
-                    let u = x * k;
-
-                    let (c, s) = fresnl(u);
-
-                    let re = 2. * c;
-                    let im = 2. * s;
-                    let abs = re.hypot(im);
-                    let phi = im.atan2(re);
-                    (u, abs, phi)
+            false => Vec::new(),
+        };
+
+        if !job.alive() {
+            return None;
+        }
+        job.report(0.75);
+
+        let far_gap = match self.rezhim {
+            Screens::Grating => (0..=FAR_GAP_N)
+                .map(|i| {
+                    let lg = 4. * i as f64 / FAR_GAP_N as f64;
+                    [lg, self.far_field_gap(10f64.powf(lg))]
                 })
                 .collect(),
+            _ => Vec::new(),
+        };
+
+        if !job.alive() {
+            return None;
+        }
+        job.report(0.85);
+
+        // phase of the sum of different frequencies has no sense
+        let difs_spectrum = match self.spectrum {
+            Spectrum::Mono => Vec::new(),
+            _ => ps
+                .iter()
+                .map(|&p| DifrPoint::new(p, self.received(p), 0.))
+                .collect(),
+        };
+
+        if !job.alive() {
+            return None;
+        }
+        job.report(0.95);
+
+        // the fitter compares the measurements with the curve of the antenna, not of the probe
+        let received = if difs_spectrum.is_empty() { &difs_aperture } else { &difs_spectrum };
+        let received_samples: Vec<(f64, Complex64)> = received
+            .iter()
+            .map(|d| (d.p_norm()[0], Complex64::new(d.p_norm()[1], 0.)))
+            .collect();
+        let features_received = extrema(&received_samples, |p| Complex64::new(self.received(p), 0.));
+        job.report(1.);
+
+        Some(Curves {
+            difs,
+            difs_model,
+            difs_aperture,
+            difs_spectrum,
+            difs_3d,
+            far_gap,
+            features,
+            features_received,
+        })
+    }
+
+    // curves from the worker
+    pub fn apply(&mut self, curves: Curves) {
+        self.difs = curves.difs;
+        self.difs_model = curves.difs_model;
+        self.difs_aperture = curves.difs_aperture;
+        self.difs_spectrum = curves.difs_spectrum;
+        self.difs_3d = curves.difs_3d;
+        self.far_gap = curves.far_gap;
+        self.features = curves.features;
+        self.features_received = curves.features_received;
+        self.stamp += 1;
+    }
+
+    // u of the edge x and of the edge -x of the second screen
+    #[inline]
+    fn ends(&self, geometry: &Geometry, x: f64) -> (f64, f64) {
+        let (k, shadow) = (geometry.k(), geometry.shadow());
+        (k * (x - shadow), k * (-x - shadow))
+    }
+
+    // open parts of the screen when its edge (or shift) is x,
+    // a transparent screen of One and Two is an opening too
+    pub fn openings(&self, x: f64) -> Vec<Opening> {
+        let (inf, t) = (f64::INFINITY, self.screen_t());
+        let screen = |a: f64, b: f64| Opening { a, b, t };
+        let transparent = self.screen_amp > 0.;
+        match (self.rezhim, transparent) {
+            (Screens::One, false) => vec![Opening::open(-inf, x)],
+            (Screens::One, true) => vec![Opening::open(-inf, x), screen(x, inf)],
+            (Screens::Two, false) => vec![Opening::open(-x, x)],
+            (Screens::Two, true) => vec![screen(-inf, -x), Opening::open(-x, x), screen(x, inf)],
+            (Screens::Intervals, _) => self.intervals.iter().map(|i| i.opening(x)).collect(),
+            (Screens::Grating, false) => self.grating.openings(x),
+            (Screens::Grating, true) => {
+                let slits = self.grating.openings(x);
+                let between: Vec<Opening> = opaque_parts(&slits, f64::INFINITY)
+                    .into_iter()
+                    .map(|(a, b)| screen(a, b))
+                    .collect();
+                slits.into_iter().chain(between).collect()
+            }
+        }
+    }
+
+    // complex transmission of the screen of One and Two
+    #[inline]
+    pub fn screen_t(&self) -> Complex64 {
+        Complex64::from_polar(self.screen_amp as f64, (self.screen_phase as f64).to_radians())
+    }
+
+    // u of all finite ends of the openings
+    fn edge_us(&self, geometry: &Geometry, x: f64) -> Vec<f64> {
+        let (k, shadow) = (geometry.k(), geometry.shadow());
+        self.openings(x)
+            .iter()
+            .flat_map(|o| [o.a, o.b])
+            .filter(|e| e.is_finite())
+            .map(|e| k * (e - shadow))
+            .collect()
+    }
+
+    // points of plots where an edge is on the geometric shadow boundary, its u = 0;
+    // u of every edge goes monotonically with p, so there is one root or none,
+    // a boundary at the end of plots (closed slit of Two) is not counted
+    fn shadow_ps(&self, p_min: f64, p_max: f64) -> Vec<f64> {
+        let us = |p: f64| {
+            let (geometry, x) = self.setup_at(p);
+            self.edge_us(&geometry, x)
         };
+        let (first, last) = (us(p_min), us(p_max));
+        if first.len() != last.len() {
+            return Vec::new();
+        }
+        let mut ps: Vec<f64> = (0..first.len())
+            .filter(|&i| first[i] * last[i] < 0.)
+            .map(|i| bisect(p_min, p_max, |p| us(p).get(i).copied().unwrap_or(0.)))
+            .collect();
+        // edges of neighbouring openings are the same
+        ps.sort_by(f64::total_cmp);
+        ps.dedup_by(|a, b| (*a - *b).abs() < 1e-9);
+        ps
+    }
+
+    // C + 0.5 and S + 0.5 for one screen, C(u1) - C(u2) and S(u1) - S(u2) for two,
+    // sum of such pieces with transmissions for intervals
+    #[inline]
+    pub fn screen_field(&self, geometry: &Geometry, x: f64) -> (f64, f64) {
+        let f = openings_field(&self.openings(x), geometry.k(), geometry.shadow());
+        (f.re, f.im)
+    }
+
+    // geometry and the edge position for the point p of plots
+    #[inline]
+    fn setup_at(&self, p: f64) -> (Geometry, f64) {
+        match self.scan {
+            Scan::Edge => (self.geometry(), self.u_to_x(p)),
+            Scan::Receiver => (self.geometry().with_receiver(p), self.x_otv.cm()),
+        }
+    }
+
+    // field in the point p of plots
+    #[inline]
+    fn field_at(&self, p: f64) -> (f64, f64) {
+        let (geometry, x) = self.setup_at(p);
+        self.screen_field(&geometry, x)
+    }
 
-        self.difs = points
+    // field received by the antenna: the total wave summed over its width
+    // and divided by the same sum of the wave without screens
+    fn field_averaged(&self, geometry: &Geometry, x: f64) -> (f64, f64) {
+        let (width, k) = (self.aperture_width.cm(), TAU / geometry.lambda);
+
+        let mut sum = Complex64::new(0., 0.);
+        let mut norm = Complex64::new(0., 0.);
+        for i in 0..APERTURE_N {
+            let t = width * ((i as f64 + 0.5) / APERTURE_N as f64 - 0.5);
+            let w = if self.aperture_cos { (PI * t / width).cos() } else { 1. };
+
+            let geometry = geometry.with_receiver(geometry.receiver + t);
+            // the factor of the curves is relative to e^(-jk·path)
+            let (amp, path) = geometry.direct();
+            let direct = Complex64::from_polar(w * amp, -k * path);
+            let (re, im) = self.screen_field(&geometry, x);
+
+            sum += direct * Complex64::new(re, im);
+            norm += direct;
+        }
+        let f = sum / norm;
+        (f.re, f.im)
+    }
+
+    // field of the point probe or of the antenna
+    #[inline]
+    fn field_received(&self, geometry: &Geometry, x: f64) -> (f64, f64) {
+        match self.aperture {
+            true => self.field_averaged(geometry, x),
+            false => self.screen_field(geometry, x),
+        }
+    }
+
+    // |F| which gets the receiver: with its antenna and power summed over the spectrum
+    fn received(&self, p: f64) -> f64 {
+        let (geometry, x) = self.setup_at(p);
+
+        let power: f64 = self
+            .spectrum
+            .samples(self.freq())
             .into_iter()
-            .map(|(u, abs, phi)| DifrPoint::new(u, abs / SQRT_2, phi))
+            .map(|(lambda, w)| {
+                let (re, im) = self.field_received(&Geometry { lambda: lambda.cm(), ..geometry }, x);
+                w * (re * re + im * im)
+            })
+            .sum();
+        power.sqrt() / SQRT_2
+    }
+
+    // |F| and phase of the point probe for receivers (x, z) behind the screen, rows are zs
+    // rows are checked for the cancel of the job, None if it is cancelled
+    pub fn field_map(&self, xs: &[f64], zs: &[f64], job: &Job) -> Option<Vec<(f64, f64)>> {
+        let geometry = self.geometry();
+        let x = self.x_otv.cm();
+
+        let mut map = Vec::with_capacity(xs.len() * zs.len());
+        for (i, &z) in zs.iter().enumerate() {
+            if !job.alive() {
+                return None;
+            }
+            map.extend(xs.iter().map(|&xr| {
+                let geometry = Geometry { l2: z, ..geometry }.with_receiver(xr);
+                let (re, im) = self.screen_field(&geometry, x);
+                (re.hypot(im) / SQRT_2, im.atan2(re))
+            }));
+            job.report((i + 1) as f64 / zs.len() as f64);
+        }
+        Some(map)
+    }
+
+    // x of the geometric shadow boundaries at distance z behind the screen
+    pub fn shadow_boundaries(&self, z: f64) -> Vec<f64> {
+        let geometry = self.geometry();
+        let mut edges: Vec<f64> = self
+            .openings(self.x_otv.cm())
+            .iter()
+            .flat_map(|o| [o.a, o.b])
+            .filter(|e| e.is_finite())
             .collect();
+        // a transparent screen and the hole share the edge
+        edges.sort_by(f64::total_cmp);
+        edges.dedup();
+        edges.into_iter().map(|e| geometry.boundary(e, z)).collect()
+    }
+
+    // opaque parts of the screen within ±x_max
+    pub fn opaque_parts(&self, x_max: f64) -> Vec<(f64, f64)> {
+        opaque_parts(&self.openings(self.x_otv.cm()), x_max)
+    }
+
+    // complex factor of the current position for other wave length, for pulses
+    pub fn current_factor(&self, lambda: Length) -> Complex64 {
+        let (geometry, x) = self.setup_at(self.cur_p());
+        let (re, im) = self.field_received(&Geometry { lambda: lambda.cm(), ..geometry }, x);
+        Complex64::new(re, im) / SQRT_2
+    }
+
+    // largest difference of |F| between Fresnel and far field over receivers at distance l2
+    fn far_field_gap(&self, l2: f64) -> f64 {
+        let geometry = Geometry { l2, ..self.geometry() };
+        let (x, t) = (self.x_otv.cm(), self.screen_t());
+        let slits = self.grating.openings(x);
+        // the pattern widens with L2, receivers go with it
+        let x_max = MAX_X as f64 * (l2 / self.l2.cm()).max(1.);
+        (0..=FAR_GAP_N * 4)
+            .map(|i| {
+                let xr = x_max * (2. * i as f64 / (FAR_GAP_N * 4) as f64 - 1.);
+                let geometry = geometry.with_receiver(xr);
+                let (k, shadow) = (geometry.k(), geometry.shadow());
+                let near = openings_field(&self.openings(x), k, shadow);
+                let far = t * Complex64::new(1., 1.) + (1. - t) * far_field(&slits, k, shadow, x);
+                (near.norm() - far.norm()).abs() / SQRT_2
+            })
+            .fold(0., f64::max)
+    }
+
+    // alternative model for the points ps of plots, empty for Fresnel,
+    // None if the job is cancelled
+    fn model_factors(&self, ps: &[f64], job: &Job) -> Option<Vec<Complex64>> {
+        let geometry = self.geometry();
+        let x = self.x_otv.cm();
+
+        let sommerfeld = |pol: Polarisation| -> Vec<Complex64> {
+            match self.scan {
+                Scan::Edge => ps
+                    .iter()
+                    .map(|&u| half_plane(self.u_to_x(u), &geometry, pol))
+                    .collect(),
+                Scan::Receiver => ps
+                    .iter()
+                    .map(|&p| half_plane(x, &geometry.with_receiver(p), pol))
+                    .collect(),
+            }
+        };
+
+        let factors = match self.model {
+            Model::Fresnel => Vec::new(),
+            Model::SommerfeldTe => sommerfeld(Polarisation::Te),
+            Model::SommerfeldTm => sommerfeld(Polarisation::Tm),
+            Model::FarField => ps
+                .iter()
+                .map(|&p| {
+                    // a transparent screen is the open wave plus slits with 1 - t
+                    let (geometry, x) = self.setup_at(p);
+                    let (k, shadow) = (geometry.k(), geometry.shadow());
+                    let t = self.screen_t();
+                    let slits = far_field(&self.grating.openings(x), k, shadow, x);
+                    (t * Complex64::new(1., 1.) + (1. - t) * slits) / SQRT_2
+                })
+                .collect(),
+            Model::Kirchhoff => {
+                let screens: Vec<Vec<Opening>> =
+                    ps.iter().map(|&u| self.openings(self.u_to_x(u))).collect();
+                kirchhoff_curve(&screens, &geometry, job)?
+            }
+        };
+        Some(factors)
+    }
+
+    // model minus Fresnel for the difference plot
+    pub fn get_difs_diff(&self) -> Vec<DifrPoint> {
+        self.difs_model
+            .iter()
+            .zip(self.difs.iter())
+            .map(|(m, f)| m.sub(f))
+            .collect()
+    }
+
+    // red point on the difference plot
+    pub fn get_current_point_diff(&self) -> Option<DifrPoint> {
+        let [p, abs] = self.get_current_point_norm();
+        let model = interpolate(&self.difs_model, p)?;
+        let [_, phi] = self.get_current_point_arg();
+        Some(model.sub(&DifrPoint::new(p, abs, phi)))
     }
 
     // get left point of size screen
     #[inline]
     pub fn get_start(&self) -> f32 {
         match self.rezhim {
-            Screens::One => -MAX_X,
+            Screens::One | Screens::Intervals | Screens::Grating => -MAX_X,
             Screens::Two => 0.,
         }
     }
 
-    // calculate radiuses of fresnels  zones
+    // radius of the last zone in the picture, zones are centered on the direct ray
+    #[inline]
+    fn max_wave(&self) -> f32 {
+        MAX_X * std::f32::consts::SQRT_2 + self.zones_center().abs()
+    }
+
+    // calculate radiuses of fresnels  zones,
+    // the ones thinner than min_width are left for the shading by density
     #[inline]
-    pub fn get_fresnel_zones(&self, is_circle: bool) -> Vec<f32> {
+    pub fn get_fresnel_zones(&self, is_circle: bool, min_width: f32) -> Vec<f32> {
         let b = self.b();
 
         // let max_wave = match self.rezhim {
         //     Screens::One => MAX_X * std::f32::consts::SQRT_2,
         //     Screens::Two => (self.x_otv * self.x_otv * 0.25 + MAX_X * MAX_X).sqrt(),
         // };
-        let max_wave = MAX_X * std::f32::consts::SQRT_2;
+        let max_wave = self.max_wave();
 
-        let last_n = 2 * (MAX_X * MAX_X / b).round() as i32;
+        let last_n = ((max_wave * max_wave / b).ceil() as usize).min(resolved_zones(b, min_width));
 
-        let mut ret = Vec::new();
+        let mut ret = Vec::with_capacity(last_n + 1);
         for n in 0..=last_n {
             let r = ((n as f32) * b).sqrt();
             ret.insert(0, r);
-            if is_circle && r >= self.x_otv {
-                ret[0] = self.x_otv;
+            if is_circle && r >= self.zones_clip() {
+                ret[0] = self.zones_clip();
                 break;
             }
             if r > max_wave {
@@ -130,43 +644,166 @@ impl Difr {
         ret
     }
 
+    // source or receiver is off the axis, the zones are not circles
+    #[inline]
+    pub fn off_axis(&self) -> bool {
+        self.angle != 0. || self.x_rec.cm() != 0.
+    }
+
+    // path via the point (x, y) of the screen minus the path via the direct ray
+    fn path_excess(&self, geometry: &Geometry, x: f64, y: f64) -> f64 {
+        let path = |x: f64, y: f64| {
+            geometry.incident(x, y).path + (x - geometry.receiver).hypot(y).hypot(geometry.l2)
+        };
+        path(x, y) - path(geometry.shadow(), 0.)
+    }
+
+    // zone through the point (x, y) of the screen by the exact path, from 1
+    #[inline]
+    pub fn zone_at(&self, x: f32, y: f32) -> usize {
+        let geometry = self.geometry();
+        let excess = self.path_excess(&geometry, x as f64, y as f64);
+        ((excess / (geometry.lambda / 2.)).ceil() as usize).max(1)
+    }
+
+    // exact boundaries of zones over the picture as closed polygons, from the outer one,
+    // the boundary n is where the path is longer than the direct one by nλ/2.
+    // It is long, so the worker makes it, None if the job is cancelled
+    pub fn get_exact_zones(&self, min_width: f32, job: &Job) -> Option<Vec<ZoneOutline>> {
+        let geometry = self.geometry();
+        let half = geometry.lambda / 2.;
+        let c = geometry.shadow();
+
+        // the last zone covers the farthest corner of the picture
+        let m = MAX_X as f64;
+        let corners = [(-m, -m), (-m, m), (m, -m), (m, m)];
+        let excess = corners
+            .iter()
+            .map(|&(x, y)| self.path_excess(&geometry, x, y))
+            .fold(0., f64::max);
+        let last_n = ((excess / half).ceil() as usize).min(resolved_zones(self.b(), min_width));
+
+        // radius from the center of zones in every direction, it only grows with n
+        let dirs: Vec<(f64, f64)> = (0..ZONE_POINTS)
+            .map(|i| (TAU * i as f64 / ZONE_POINTS as f64).sin_cos())
+            .map(|(sin, cos)| (cos, sin))
+            .collect();
+        let mut rhos = vec![0f64; ZONE_POINTS];
+
+        let mut ret = Vec::new();
+        for n in 1..=last_n {
+            if !job.alive() {
+                return None;
+            }
+            job.report(n as f64 / last_n as f64);
+            let target = n as f64 * half;
+            let zone = dirs
+                .iter()
+                .zip(rhos.iter_mut())
+                .map(|(&(cos, sin), rho)| {
+                    let excess = |r: f64| self.path_excess(&geometry, c + r * cos, r * sin);
+                    let (mut lo, mut hi) = (*rho, rho.max(half) * 2.);
+                    while excess(hi) < target && hi < 1e6 {
+                        (lo, hi) = (hi, hi * 2.);
+                    }
+                    for _ in 0..40 {
+                        let mid = (lo + hi) / 2.;
+                        if excess(mid) < target {
+                            lo = mid;
+                        } else {
+                            hi = mid;
+                        }
+                    }
+                    *rho = hi;
+                    ((c + hi * cos) as f32, (hi * sin) as f32)
+                })
+                .collect();
+            ret.insert(0, zone);
+        }
+        Some(ret)
+    }
+
+    // radius where zones get thinner than min_width, None if it is out of the picture
+    pub fn lod_radius(&self, min_width: f32) -> Option<f32> {
+        let b = self.b();
+        let r = (resolved_zones(b, min_width) as f32 * b).sqrt();
+        (r < self.max_wave()).then_some(r)
+    }
+
+    // the same for strips, their coefficient is 2/k² instead of b
+    pub fn lod_strip(&self, min_width: f32) -> Option<f32> {
+        let b = 2. / (self.geometry().k() as f32).powi(2);
+        let d = (resolved_zones(b, min_width) as f32 * b).sqrt();
+        (d < MAX_X + self.zones_center().abs()).then_some(d)
+    }
+
+    // distances of boundaries of strip zones from the center of zones,
+    // the phase of the strip n is nπ, u = √(2n)
+    #[inline]
+    pub fn get_strip_zones(&self, min_width: f32) -> Vec<f32> {
+        let k = self.geometry().k() as f32;
+        let max_wave = MAX_X + self.zones_center().abs();
+        let last_n = resolved_zones(2. / (k * k), min_width);
+
+        let mut ret = Vec::new();
+        for n in 0..=last_n {
+            let d = (2. * n as f32).sqrt() / k;
+            ret.push(d);
+            if d > max_wave {
+                break;
+            }
+        }
+        ret
+    }
+
+    // center of zones on the screen, it is shifted for oblique wave
+    #[inline]
+    pub fn zones_center(&self) -> f32 {
+        self.geometry().shadow() as f32
+    }
+
+    // zones behind the circle hole are not needed
+    #[inline]
+    pub fn zones_clip(&self) -> f32 {
+        self.x_otv.cm() as f32 + self.zones_center().abs()
+    }
+
     // checking if change
     #[inline]
     pub fn is_cheng(&self) -> bool {
         self.x_otv != self.x_otv_c
+            || self.intervals != self.intervals_c
+            || self.grating != self.grating_c
+            || self.screen_amp != self.screen_amp_c
+            || self.screen_phase != self.screen_phase_c
             || self.l1 != self.l1_c
             || self.l2 != self.l2_c
             || self.lambda != self.lambda_c
-    }
-
-    // blockerator for max vawes for fresnels zones
-    #[inline]
-    pub fn not_sale_cpu_usage(&self) -> bool {
-        self.b() > 6.
+            || self.plane_wave != self.plane_wave_c
+            || self.angle != self.angle_c
+            || self.x_rec != self.x_rec_c
     }
 
     // update copies values
     #[inline]
     pub fn cheng_copes(&mut self) {
         self.x_otv_c = self.x_otv;
+        self.intervals_c.clone_from(&self.intervals);
+        self.grating_c = self.grating;
+        self.screen_amp_c = self.screen_amp;
+        self.screen_phase_c = self.screen_phase;
         self.l1_c = self.l1;
         self.l2_c = self.l2;
         self.lambda_c = self.lambda;
-    }
-
-    // return last values
-    #[inline]
-    pub fn backup_copes(&mut self) {
-        self.x_otv = self.x_otv_c;
-        self.l1 = self.l1_c;
-        self.l2 = self.l2_c;
-        self.lambda = self.lambda_c;
+        self.plane_wave_c = self.plane_wave;
+        self.angle_c = self.angle;
+        self.x_rec_c = self.x_rec;
     }
 
     // for 3D plot in 1 screen mode
     #[inline]
     pub fn get_current_point_3d(&self) -> (f64, f64, f64) {
-        let u = self.cur_u();
+        let (u, _) = self.ends(&self.geometry(), self.x_otv.cm());
 
         let (c, s) = fresnl(u);
         (u, c, s)
@@ -175,81 +812,214 @@ impl Difr {
     // for 3D plot in 2 screens mode
     #[inline]
     pub fn get_current_points_3d(&self) -> ((f64, f64, f64), (f64, f64, f64)) {
-        let u = self.cur_u();
+        let (u1, u2) = self.ends(&self.geometry(), self.x_otv.cm());
 
-        let (c, s) = fresnl(u);
-        ((u, c, s), (-u, -c, -s))
+        let (c1, s1) = fresnl(u1);
+        let (c2, s2) = fresnl(u2);
+        ((u1, c1, s1), (u2, c2, s2))
+    }
+
+    // pieces of the spiral of every interval with its transmission,
+    // infinite ends are cut by the 3D plot
+    pub fn get_current_segments_3d(&self) -> Vec<(Complex64, [Point3; 2])> {
+        let geometry = self.geometry();
+        let (k, shadow) = (geometry.k(), geometry.shadow());
+        let (u_min, u_max) = match (self.difs_3d.first(), self.difs_3d.last()) {
+            (Some(first), Some(last)) => (first.0, last.0),
+            _ => (-1., 1.),
+        };
+        let point = |x: f64| {
+            let u = k * (x - shadow);
+            let f = fresnel_end(u);
+            (u.clamp(u_min, u_max), f.re, f.im)
+        };
+        self.openings(self.x_otv.cm())
+            .iter()
+            .map(|o| (o.t, [point(o.a), point(o.b)]))
+            .collect()
     }
 
     // for abs plot
     #[inline]
     pub fn get_current_point_norm(&self) -> [f64; 2] {
-        let u = self.cur_u();
-        self.get_point_norm(u)
+        self.get_point_norm(self.cur_p())
     }
 
     // for red point on abs plot
     #[inline]
-    pub fn get_point_norm(&self, u: f64) -> [f64; 2] {
-        match self.rezhim {
-            Screens::One => {
-                let (c, s) = fresnl(u);
-                [u, (s + 0.5).hypot(c + 0.5) / SQRT_2]
-            }
-            Screens::Two => {
-                let (c, s) = fresnl(u);
-                [u, (s * 2.).hypot(c * 2.) / SQRT_2]
-            }
-        }
+    pub fn get_point_norm(&self, p: f64) -> [f64; 2] {
+        let (re, im) = self.field_at(p);
+        [p, re.hypot(im) / SQRT_2]
+    }
+
+    // |F| which the receiver gets, the same as get_point_norm for a point probe
+    // and a single frequency
+    #[inline]
+    pub fn get_point_norm_received(&self, p: f64) -> [f64; 2] {
+        [p, self.received(p)]
     }
 
     // for red point on angel plot
     #[inline]
     pub fn get_current_point_arg(&self) -> [f64; 2] {
-        let u = self.cur_u();
-        match self.rezhim {
-            Screens::One => {
-                let (c, s) = fresnl(u);
-                [u, (s + 0.5).atan2(c + 0.5)]
-            }
-            Screens::Two => {
-                let (c, s) = fresnl(u );
-                [u, (s * 2.).atan2(c * 2.)]
-            }
-        }
+        let p = self.cur_p();
+        let (re, im) = self.field_at(p);
+        [p, im.atan2(re)]
+    }
+
+    // source, screen and receiver in f64
+    #[inline]
+    pub fn geometry(&self) -> Geometry {
+        Geometry::new(
+            self.l1.cm(),
+            self.l2.cm(),
+            self.lambda.cm(),
+            self.plane_wave,
+            (self.angle as f64).to_radians(),
+        )
+        .with_receiver(self.x_rec.cm())
+        .with_beam(self.beam)
+    }
+
+    #[inline]
+    pub fn freq(&self) -> Frequency {
+        self.lambda.frequency()
     }
 
     // wavenumber
     #[inline]
     pub fn k(&self) -> f64 {
-        (2. * (self.l1 + self.l2) / (self.lambda * self.l1 * self.l2)).sqrt() as f64
+        self.geometry().k()
     }
 
     // coefficient for fresnels zones
     #[inline]
     fn b(&self) -> f32 {
-        (self.lambda * self.l1 * self.l2) / (self.l1 + self.l2)
+        self.geometry().b() as f32
+    }
+
+    // edge position where u = 0: the direct ray for one screen, the axis for two
+    #[inline]
+    fn x_zero(&self) -> f64 {
+        self.x_zero_in(&self.geometry())
+    }
+
+    #[inline]
+    fn x_zero_in(&self, geometry: &Geometry) -> f64 {
+        match self.rezhim {
+            Screens::One | Screens::Intervals | Screens::Grating => geometry.shadow(),
+            Screens::Two => 0.,
+        }
+    }
+
+    #[inline]
+    fn x_to_u(&self, x: f64) -> f64 {
+        self.k() * (x - self.x_zero())
+    }
+
+    #[inline]
+    fn u_to_x(&self, u: f64) -> f64 {
+        u / self.k() + self.x_zero()
+    }
+
+    // point of plots for the position x of the moved thing (edge or receiver)
+    #[inline]
+    pub fn x_to_p(&self, x: f64) -> f64 {
+        match self.scan {
+            Scan::Edge => self.x_to_u(x),
+            Scan::Receiver => x,
+        }
+    }
+
+    // position of the moved thing for the point p of plots
+    #[inline]
+    pub fn p_to_x(&self, p: f64) -> f64 {
+        match self.scan {
+            Scan::Edge => self.u_to_x(p),
+            Scan::Receiver => p,
+        }
+    }
+
+    // dx/du of the moved thing at x in the geometry, the scale of the fringes
+    pub fn x_per_u(&self, geometry: &Geometry, x: f64) -> f64 {
+        const H: f64 = 1e-3;
+        let u = |x: f64| match self.scan {
+            Scan::Edge => geometry.k() * (x - self.x_zero_in(geometry)),
+            Scan::Receiver => self.ends(&geometry.with_receiver(x), self.x_otv.cm()).0,
+        };
+        2. * H / (u(x + H) - u(x - H))
+    }
+
+    // u of the edge for the position x of the moved thing
+    #[inline]
+    pub fn x_to_edge_u(&self, x: f64) -> f64 {
+        match self.scan {
+            Scan::Edge => self.x_to_u(x),
+            Scan::Receiver => self.ends(&self.geometry().with_receiver(x), self.x_otv.cm()).0,
+        }
+    }
+
+    // names of the bottom and the top axes of plots
+    #[inline]
+    pub fn axis_names(&self) -> (&'static str, &'static str) {
+        match self.scan {
+            Scan::Edge => ("u", "x"),
+            Scan::Receiver => ("x приёмника", "u"),
+        }
+    }
+
+    // converter from the bottom axis of plots to the top one,
+    // it owns copies, so it can live in formatters of plots
+    pub fn second_axis(&self) -> Box<dyn Fn(f64) -> f64> {
+        match self.scan {
+            Scan::Edge => {
+                let (k, x0) = (self.k(), self.x_zero());
+                Box::new(move |u| u / k + x0)
+            }
+            Scan::Receiver => {
+                let (geometry, x) = (self.geometry(), self.x_otv.cm());
+                Box::new(move |p| {
+                    let geometry = geometry.with_receiver(p);
+                    geometry.k() * (x - geometry.shadow())
+                })
+            }
+        }
     }
 
     // come on it's obvious
     #[inline]
-    fn cur_u(&self) -> f64 {
-        self.k() * self.x_otv as f64
+    fn cur_p(&self) -> f64 {
+        match self.scan {
+            Scan::Edge => self.x_to_u(self.x_otv.cm()),
+            Scan::Receiver => self.x_rec.cm(),
+        }
     }
 
     #[inline]
     pub fn get_student_points(&'_ self) -> RefMut<'_, Vec<(f64, f64)>> {
-        match self.rezhim {
-            Screens::One => self.student_points_1.borrow_mut(),
-            Screens::Two => self.student_points_2.borrow_mut(),
+        match (self.scan, self.rezhim) {
+            (Scan::Edge, Screens::One) => self.student_points_1.borrow_mut(),
+            (Scan::Edge, Screens::Two) => self.student_points_2.borrow_mut(),
+            (Scan::Receiver, Screens::One) => self.student_points_r1.borrow_mut(),
+            (Scan::Receiver, Screens::Two) => self.student_points_r2.borrow_mut(),
+            (Scan::Edge, Screens::Intervals) => self.student_points_n.borrow_mut(),
+            (Scan::Receiver, Screens::Intervals) => self.student_points_rn.borrow_mut(),
+            (Scan::Edge, Screens::Grating) => self.student_points_g.borrow_mut(),
+            (Scan::Receiver, Screens::Grating) => self.student_points_rg.borrow_mut(),
         }
     }
 
     #[inline]
     pub fn get_max_i(&mut self) -> &mut f64 {
-        match self.rezhim {
-            Screens::One => &mut self.max_i_1,
-            Screens::Two => &mut self.max_i_2,
+        match (self.scan, self.rezhim) {
+            (Scan::Edge, Screens::One) => &mut self.max_i_1,
+            (Scan::Edge, Screens::Two) => &mut self.max_i_2,
+            (Scan::Receiver, Screens::One) => &mut self.max_i_r1,
+            (Scan::Receiver, Screens::Two) => &mut self.max_i_r2,
+            (Scan::Edge, Screens::Intervals) => &mut self.max_i_n,
+            (Scan::Receiver, Screens::Intervals) => &mut self.max_i_rn,
+            (Scan::Edge, Screens::Grating) => &mut self.max_i_g,
+            (Scan::Receiver, Screens::Grating) => &mut self.max_i_rg,
         }
     }
 
@@ -269,29 +1039,78 @@ impl Difr {
 
 impl Default for Difr {
     fn default() -> Self {
-        let lambda = 3.;
-
         Self {
-            x_otv: 10.,
-            l1: 40.,
-            l2: 40.,
-            lambda,
-            freq: converter_freq(lambda),
+            x_otv: Length::from_cm(10.),
+            intervals: Preset::Slits.intervals(),
+            grating: Grating::default(),
+            screen_amp: 0.,
+            screen_phase: 0.,
+            l1: Length::from_cm(40.),
+            l2: Length::from_cm(40.),
+            lambda: Length::from_cm(3.),
+            plane_wave: false,
+            angle: 0.,
+            x_rec: Length::from_cm(0.),
+            beam: Beam::Ideal,
+            aperture: false,
+            aperture_width: Length::from_cm(5.),
+            aperture_cos: true,
+            spectrum: Spectrum::Mono,
             rezhim: Screens::One,
+            scan: Scan::Edge,
+            model: Model::Fresnel,
 
             difs: Vec::new(),
+            difs_model: Vec::new(),
+            difs_aperture: Vec::new(),
+            difs_spectrum: Vec::new(),
             difs_3d: Vec::new(),
+            far_gap: Vec::new(),
+            features: Vec::new(),
+            features_received: Vec::new(),
+            stamp: 0,
 
-            x_otv_c: 0.,
-            l1_c: 0.,
-            l2_c: 0.,
-            lambda_c: 0.,
+            rebuild: false,
+            x_otv_c: Length::from_cm(0.),
+            intervals_c: Vec::new(),
+            grating_c: Grating::default(),
+            screen_amp_c: 0.,
+            screen_phase_c: 0.,
+            l1_c: Length::from_cm(0.),
+            l2_c: Length::from_cm(0.),
+            lambda_c: Length::from_cm(0.),
+            plane_wave_c: false,
+            angle_c: 0.,
+            x_rec_c: Length::from_cm(0.),
 
             // max_abs: 0.,
             student_points_1: RefCell::new(Vec::new()),
             student_points_2: RefCell::new(Vec::new()),
+            student_points_r1: RefCell::new(Vec::new()),
+            student_points_r2: RefCell::new(Vec::new()),
+            student_points_n: RefCell::new(Vec::new()),
+            student_points_rn: RefCell::new(Vec::new()),
+            student_points_g: RefCell::new(Vec::new()),
+            student_points_rg: RefCell::new(Vec::new()),
             max_i_1: 0.,
             max_i_2: 0.,
+            max_i_r1: 0.,
+            max_i_r2: 0.,
+            max_i_n: 0.,
+            max_i_rn: 0.,
+            max_i_g: 0.,
+            max_i_rg: 0.,
         }
     }
 }
+
+// number of zones from the center which are not thinner than min_width:
+// √(nb) - √((n-1)b) = w gives √n = (q + 1/q)/2 with q = w/√b
+#[inline]
+fn resolved_zones(b: f32, min_width: f32) -> usize {
+    let q = min_width / b.sqrt();
+    if q <= 0. {
+        return usize::MAX;
+    }
+    ((q + 1. / q) / 2.).powi(2).floor().max(1.) as usize
+}
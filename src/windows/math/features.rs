@@ -0,0 +1,173 @@
+use num_complex::Complex64;
+
+// Numbers which students read from the plots: maxima and minima of |F| (zeros
+// among them), |F| on the geometric shadow boundaries and the points where the
+// phase wraps from π to -π. The samples of the plots give brackets, then the
+// points are refined on the field itself.
+
+const REFINE_STEPS: usize = 60; // steps of bisection and of golden section
+const ZERO: f64 = 1e-3; // a minimum with smaller |F| is a zero
+const FLAT: f64 = 1e-9; // smaller changes of |F| between samples are a plateau
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum FeatureKind {
+    Max,
+    Min,
+    Zero,
+    Shadow, // the receiver is on the geometric shadow boundary of an edge
+    Wrap,   // the phase goes over ±π
+}
+
+impl FeatureKind {
+    pub const ALL: [FeatureKind; 5] = [
+        FeatureKind::Max,
+        FeatureKind::Min,
+        FeatureKind::Zero,
+        FeatureKind::Shadow,
+        FeatureKind::Wrap,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FeatureKind::Max => "максимум",
+            FeatureKind::Min => "минимум",
+            FeatureKind::Zero => "ноль",
+            FeatureKind::Shadow => "граница тени",
+            FeatureKind::Wrap => "скачок фазы",
+        }
+    }
+}
+
+/// point p of the plots and the field F there
+#[derive(Clone, Debug)]
+pub struct Feature {
+    pub kind: FeatureKind,
+    pub p: f64,
+    pub field: Complex64,
+}
+
+impl Feature {
+    #[inline]
+    pub fn at(kind: FeatureKind, p: f64, field: impl Fn(f64) -> Complex64) -> Self {
+        Self { kind, p, field: field(p) }
+    }
+}
+
+/// maxima and minima of |F| between the samples (p, F), a plateau gives one point
+pub fn extrema(samples: &[(f64, Complex64)], field: impl Fn(f64) -> Complex64) -> Vec<Feature> {
+    samples
+        .windows(3)
+        .filter_map(|w| {
+            let (a, b, c) = (w[0].1.norm(), w[1].1.norm(), w[2].1.norm());
+            let sign = if b - a > FLAT && b >= c {
+                1.
+            } else if a - b > FLAT && b <= c {
+                -1.
+            } else {
+                return None;
+            };
+            let p = golden(w[0].0, w[2].0, |p| sign * field(p).norm());
+            let feature = Feature::at(FeatureKind::Max, p, &field);
+            let kind = match (sign > 0., feature.field.norm() < ZERO) {
+                (true, _) => FeatureKind::Max,
+                (false, false) => FeatureKind::Min,
+                (false, true) => FeatureKind::Zero,
+            };
+            Some(Feature { kind, ..feature })
+        })
+        .collect()
+}
+
+/// points where F crosses the negative real axis, there the phase wraps
+pub fn phase_wraps(samples: &[(f64, Complex64)], field: impl Fn(f64) -> Complex64) -> Vec<Feature> {
+    samples
+        .windows(2)
+        .filter(|w| w[0].1.re < 0. && w[1].1.re < 0. && (w[0].1.im < 0.) != (w[1].1.im < 0.))
+        .map(|w| {
+            let p = bisect(w[0].0, w[1].0, |p| field(p).im);
+            Feature::at(FeatureKind::Wrap, p, &field)
+        })
+        .collect()
+}
+
+/// root of f on [a, b] where f changes its sign
+pub fn bisect(mut a: f64, mut b: f64, f: impl Fn(f64) -> f64) -> f64 {
+    let negative = f(a) < 0.;
+    for _ in 0..REFINE_STEPS {
+        let m = (a + b) / 2.;
+        if (f(m) < 0.) == negative {
+            a = m;
+        } else {
+            b = m;
+        }
+    }
+    (a + b) / 2.
+}
+
+// point of the largest f on [a, b], f has one maximum there
+fn golden(mut a: f64, mut b: f64, f: impl Fn(f64) -> f64) -> f64 {
+    const R: f64 = 0.618_033_988_749_894_9; // (√5 - 1)/2
+
+    let (mut x1, mut x2) = (b - R * (b - a), a + R * (b - a));
+    let (mut f1, mut f2) = (f(x1), f(x2));
+    for _ in 0..REFINE_STEPS {
+        if f1 < f2 {
+            (a, x1, f1) = (x1, x2, f2);
+            x2 = a + R * (b - a);
+            f2 = f(x2);
+        } else {
+            (b, x2, f2) = (x2, x1, f1);
+            x1 = b - R * (b - a);
+            f1 = f(x1);
+        }
+    }
+    (a + b) / 2.
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::windows::math::difr::Difr;
+    use crate::windows::math::worker::Job;
+
+    // the default screen is one edge moved across the beam, p is u
+    fn knife_edge() -> Vec<Feature> {
+        let mut fz = Difr::default();
+        let curves = fz.compute(&Job::detached()).unwrap();
+        fz.apply(curves);
+        fz.features
+    }
+
+    #[test]
+    fn shadow_boundary() {
+        let features = knife_edge();
+        let shadow: Vec<_> = features.iter().filter(|f| f.kind == FeatureKind::Shadow).collect();
+        assert_eq!(shadow.len(), 1);
+        assert!(shadow[0].p.abs() < 1e-9, "{}", shadow[0].p);
+        assert!((shadow[0].field.norm() - 0.5).abs() < 1e-9, "{}", shadow[0].field.norm());
+    }
+
+    // the brightest fringe of the lit side, |F|² = 1.3704
+    #[test]
+    fn first_maximum() {
+        let features = knife_edge();
+        let max = features
+            .iter()
+            .filter(|f| f.kind == FeatureKind::Max && f.p > 0.)
+            .min_by(|a, b| a.p.total_cmp(&b.p))
+            .unwrap();
+        assert!((max.p - 1.2172).abs() < 1e-3, "{}", max.p);
+        assert!((max.field.norm() - 1.1706).abs() < 1e-3, "{}", max.field.norm());
+    }
+
+    #[test]
+    fn golden_and_bisect() {
+        let p = golden(0., 3., |x| x.sin());
+        assert!((p - std::f64::consts::FRAC_PI_2).abs() < 1e-7, "{p}");
+        let p = bisect(1., 2., |x| x * x - 2.);
+        assert!((p - std::f64::consts::SQRT_2).abs() < 1e-12, "{p}");
+        // the sign of the ends does not matter
+        let p = bisect(2., 1., |x| x * x - 2.);
+        assert!((p - std::f64::consts::SQRT_2).abs() < 1e-12, "{p}");
+    }
+}
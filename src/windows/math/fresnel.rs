@@ -0,0 +1,252 @@
+use num_complex::Complex64;
+use std::f64::consts::{FRAC_PI_2, PI};
+
+// Fresnel integrals C(u) = ∫cos(π/2·t²)dt, S(u) = ∫sin(π/2·t²)dt over [0, u]
+// and their auxiliary functions f, g (Abramowitz & Stegun 7.3.1-2, 7.3.5-6):
+//   C = 1/2 + f·sin(π/2·u²) - g·cos(π/2·u²)
+//   S = 1/2 - f·cos(π/2·u²) - g·sin(π/2·u²)
+// Small |u| is the power series. Farther f and g come from the continued
+// fraction of erfc (modified Lentz), far away from the asymptotic series.
+// f and g are smooth and small, all the oscillation is in the phase π/2·u²,
+// which is reduced from the exact u², so large u keep their digits.
+
+const SERIES_MAX: f64 = 1.5; // |u| below which the power series is summed
+const HUGE: f64 = 1e16; // f is below the precision, C = S = 1/2
+const MAX_TERMS: usize = 200;
+
+/// how far the sums are taken
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Precision {
+    Single, // ~1e-7, the plots are in f32
+    Double, // ~1e-15
+}
+
+impl Precision {
+    fn eps(self) -> f64 {
+        match self {
+            Precision::Single => f32::EPSILON as f64,
+            Precision::Double => f64::EPSILON,
+        }
+    }
+
+    // from here the asymptotic series reaches eps
+    fn far(self) -> f64 {
+        match self {
+            Precision::Single => 4.,
+            Precision::Double => 6.,
+        }
+    }
+}
+
+/// C(u) and S(u)
+pub fn fresnel(u: f64, precision: Precision) -> (f64, f64) {
+    let x = u.abs();
+    let (c, s) = if x < SERIES_MAX {
+        series(x, precision.eps())
+    } else if x > HUGE {
+        (0.5, 0.5)
+    } else {
+        let (f, g) = auxiliary(x, precision);
+        let (sin, cos) = phase(x);
+        (0.5 + f * sin - g * cos, 0.5 - f * cos - g * sin)
+    };
+    (c.copysign(u), s.copysign(u))
+}
+
+/// f(u) and g(u), they are even here: C and S of negative u are odd
+pub fn auxiliary(u: f64, precision: Precision) -> (f64, f64) {
+    let x = u.abs();
+    if x < SERIES_MAX {
+        let (c, s) = series(x, precision.eps());
+        let (sin, cos) = phase(x);
+        let (a, b) = (0.5 - c, 0.5 - s);
+        (b * cos - a * sin, a * cos + b * sin)
+    } else if x >= precision.far() {
+        asymptotic(x, precision.eps()).unwrap_or_else(|| continued_fraction(x, precision.eps()))
+    } else {
+        continued_fraction(x, precision.eps())
+    }
+}
+
+/// (S, C) in the order of the `fresnel` crate, the plots are built on it
+#[inline]
+pub fn fresnl(u: f64) -> (f64, f64) {
+    let (c, s) = fresnel(u, Precision::Double);
+    (s, c)
+}
+
+// sin and cos of π/2·x², x² is split exactly into p + e and p is taken mod 4
+#[inline]
+fn phase(x: f64) -> (f64, f64) {
+    let p = x * x;
+    let e = x.mul_add(x, -p);
+    (FRAC_PI_2 * (p % 4. + e)).sin_cos()
+}
+
+// x·z^k/k!/(2k+1) with z = π/2·x², even k go to C, odd k to S,
+// the signs alternate in pairs
+fn series(x: f64, eps: f64) -> (f64, f64) {
+    let z = FRAC_PI_2 * x * x;
+    let (mut c, mut s) = (0., 0.);
+    let mut term = x; // x·z^k/k!
+    let mut small = false; // the term of the other integral is already small
+    for k in 0..MAX_TERMS {
+        let part = term / (2 * k + 1) as f64;
+        let sum = if k % 2 == 0 { &mut c } else { &mut s };
+        *sum += if k % 4 < 2 { part } else { -part };
+        let now = part <= eps * sum.abs();
+        if now && small {
+            break;
+        }
+        small = now;
+        term *= z / (k + 1) as f64;
+    }
+    (c, s)
+}
+
+// g + jf = x·h, h is the continued fraction of erfc at (1 - j)·√π/2·x
+fn continued_fraction(x: f64, eps: f64) -> (f64, f64) {
+    const TINY: f64 = 1e-300;
+
+    let one = Complex64::new(1., 0.);
+    let mut b = Complex64::new(1., -PI * x * x);
+    let mut c = Complex64::new(1. / TINY, 0.);
+    let mut d = one / b;
+    let mut h = d;
+    for k in 1..MAX_TERMS {
+        let n = (2 * k - 1) as f64;
+        let a = -n * (n + 1.);
+        b += 4.;
+        d = one / (d * a + b);
+        c = b + a / c;
+        let delta = c * d;
+        h *= delta;
+        if (delta - one).l1_norm() < eps {
+            break;
+        }
+    }
+    let h = h * x;
+    (h.im, h.re)
+}
+
+// A&S 7.3.27-28, the terms fall while 4m < πx², None if eps is not reached
+fn asymptotic(x: f64, eps: f64) -> Option<(f64, f64)> {
+    let y = (PI * x * x).powi(-2);
+    let (mut f, mut g) = (1., 1.);
+    let (mut tf, mut tg) = (1., 1.);
+    for m in 1..MAX_TERMS {
+        let m4 = (4 * m) as f64;
+        let (nf, ng) = (-tf * (m4 - 3.) * (m4 - 1.) * y, -tg * (m4 - 1.) * (m4 + 1.) * y);
+        if nf.abs() > tf.abs() || ng.abs() > tg.abs() {
+            return None;
+        }
+        (tf, tg) = (nf, ng);
+        f += tf;
+        g += tg;
+        if tf.abs() < eps && tg.abs() < eps {
+            return Some((f / (PI * x), g / (PI * PI * x * x * x)));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hint::black_box;
+    use std::time::Instant;
+
+    // x, C, S, f, g in the normalisation of A&S 7.3, computed with 50 digits at
+    // the f64 value of x (large x are not exact), the first 7 digits are those
+    // of the tables of A&S; points around the ends of the series and of the
+    // asymptotics are included
+    const TABLE: [[f64; 5]; 21] = [
+        [0.1, 0.09999753262708508, 0.0005235895476122107, 0.4931318256066236, 0.40779855452993036],
+        [0.5, 0.4923442258714464, 0.06473243285999927, 0.39920505852570226, 0.17364269961323775],
+        [1., 0.7798934003768229, 0.43825914739035476, 0.2798934003768228, 0.061740852609645236],
+        [1.4, 0.5430957835462564, 0.7135250773634121, 0.21580974103679207, 0.029603393321537726],
+        [1.5, 0.4452611760398215, 0.6975049600820931, 0.20341843122601397, 0.025009796942798094],
+        [1.6, 0.36546168344048763, 0.6388876835093809, 0.19219389591936833, 0.02125684888699405],
+        [2., 0.48825340607534073, 0.34341567836369824, 0.15658432163630176, 0.011746593924659246],
+        [2.5, 0.45741300964177706, 0.6191817558195929, 0.1264069204948636, 0.006263634649122138],
+        [3., 0.6057207892976856, 0.496312998967375, 0.10572078929768564, 0.003687001032624964],
+        [3.9, 0.42233271026093333, 0.47520240235068867, 0.08151225949002927, 0.0016971439006161974],
+        [4., 0.4984260330381776, 0.42051575424692844, 0.07948424575307157, 0.0015739669618223845],
+        [4.1, 0.5736956314512053, 0.47579825703282796, 0.07755405813546998, 0.0014623694338548824],
+        [5., 0.5636311887040122, 0.49919138191711687, 0.06363118870401223, 0.0008086180828831132],
+        [5.9, 0.44859195316982997, 0.5163306915041534, 0.05393733390585162, 0.0004927220569959352],
+        [6., 0.4995314678555011, 0.4469607612369303, 0.05303923876306972, 0.0004685321444988798],
+        [6.1, 0.5495022012639654, 0.5164770827951036, 0.05217052196399923, 0.00044589843991166094],
+        [8., 0.49980218037719715, 0.46021421439301446, 0.039785785606985515, 0.00019781962280286444],
+        [10., 0.49989869420551575, 0.46816997858488224, 0.03183002141511776, 0.00010130579448427639],
+        [100.5, 0.5012119666815136, 0.4970737927674261, 0.0031672625396552846, 9.981643686998764e-08],
+        [1000.25, 0.500031191926018, 0.4996833020274881, 0.0003182303286015436, 1.0124523073407067e-10],
+        [12345.678, 0.500023334695318, 0.5000109663298015, 2.578310289510148e-05, 5.384624440224266e-14],
+    ];
+
+    fn check(precision: Precision, tol: f64) {
+        for [x, c, s, f, g] in TABLE {
+            let (c1, s1) = fresnel(x, precision);
+            let (f1, g1) = auxiliary(x, precision);
+            assert!((c1 - c).abs() < tol && (s1 - s).abs() < tol, "C, S at {x}: {c1} {s1}");
+            // f and g relative to |f + jg|, g is much smaller far away
+            let size = f.hypot(g);
+            assert!((f1 - f).abs() < tol * size && (g1 - g).abs() < tol * size, "f, g at {x}: {f1} {g1}");
+            assert_eq!(fresnel(-x, precision), (-c1, -s1));
+        }
+    }
+
+    #[test]
+    fn table_double() {
+        check(Precision::Double, 2e-15);
+    }
+
+    #[test]
+    fn table_single() {
+        check(Precision::Single, 2e-7);
+    }
+
+    #[test]
+    fn limits() {
+        assert_eq!(fresnel(0., Precision::Double), (0., 0.));
+        assert_eq!(fresnel(f64::INFINITY, Precision::Double), (0.5, 0.5));
+        assert_eq!(fresnel(f64::NEG_INFINITY, Precision::Double), (-0.5, -0.5));
+        // the first terms of the series
+        let (c, s) = fresnel(1e-5, Precision::Double);
+        assert_eq!(c, 1e-5);
+        assert!((s / (PI / 6. * 1e-15) - 1.).abs() < 1e-15);
+    }
+
+    // the crate loses digits only in the phase of large u
+    #[test]
+    fn same_as_crate() {
+        for i in -20000..=20000 {
+            let u = i as f64 / 1000.;
+            let (s0, c0) = ::fresnel::fresnl(u);
+            let (c, s) = fresnel(u, Precision::Double);
+            assert!((c - c0).abs() < 1e-14 && (s - s0).abs() < 1e-14, "at {u}");
+        }
+    }
+
+    // cargo test --release bench -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench() {
+        const N: usize = 1_000_000;
+        let ranges = [("|u| < 1.5", 1.5), ("|u| < 10", 10.), ("|u| < 1000", 1000.)];
+        for (name, max) in ranges {
+            let us: Vec<f64> = (0..N).map(|i| max * (2. * i as f64 / N as f64 - 1.)).collect();
+            let time = |f: &dyn Fn(f64) -> (f64, f64)| {
+                let start = Instant::now();
+                for &u in &us {
+                    black_box(f(black_box(u)));
+                }
+                start.elapsed().as_secs_f64() * 1e9 / N as f64
+            };
+            let crate_ns = time(&|u| ::fresnel::fresnl(u));
+            let double_ns = time(&|u| fresnel(u, Precision::Double));
+            let single_ns = time(&|u| fresnel(u, Precision::Single));
+            println!("{name}: crate {crate_ns:.1} ns, double {double_ns:.1} ns, single {single_ns:.1} ns");
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use crate::windows::math::difr::Difr;
+use crate::windows::math::features::{bisect, FeatureKind};
+use crate::windows::math::geometry::{Geometry, Source};
+use crate::windows::math::units::Length;
+
+// Wavelength (or a distance) from the measured fringes.
+// Extrema of the measured curve are the vertices of parabolas through three
+// points of the table, they are matched to the nearest theoretical extrema of
+// the same kind. An extremum keeps its u, so the positions go as x = A + B·u,
+// and the slope B grows with λ and with the distances. The straight line through
+// the pairs (u, x) gives B and its error, then the unknown is found so that the
+// theory has the same slope. A is the zero of the scale, it is not needed.
+// Where the extrema move in u with λ (slits seen by the receiver) the answer is
+// approximate: it is taken into the parameters and found again.
+
+const MIN_PAIRS: usize = 3;
+const SEARCH: f64 = 100.; // the unknown is looked for from q/SEARCH to q·SEARCH
+const STEP: f64 = 1e-3; // relative step of the derivative for the error
+
+/// what is found from the slope
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Unknown {
+    Lambda,
+    L1,
+    L2,
+}
+
+impl Unknown {
+    pub const ALL: [Unknown; 3] = [Unknown::Lambda, Unknown::L1, Unknown::L2];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Unknown::Lambda => "λ",
+            Unknown::L1 => "L1",
+            Unknown::L2 => "L2",
+        }
+    }
+
+    pub fn value(&self, fz: &Difr) -> Length {
+        match self {
+            Unknown::Lambda => fz.lambda,
+            Unknown::L1 => fz.l1,
+            Unknown::L2 => fz.l2,
+        }
+    }
+
+    pub fn value_mut<'a>(&self, fz: &'a mut Difr) -> &'a mut Length {
+        match self {
+            Unknown::Lambda => &mut fz.lambda,
+            Unknown::L1 => &mut fz.l1,
+            Unknown::L2 => &mut fz.l2,
+        }
+    }
+
+    // the geometry where the unknown is q cm, a plane wave has no L1
+    fn trial(&self, geometry: Geometry, q: f64) -> Option<Geometry> {
+        match (self, geometry.source) {
+            (Unknown::Lambda, _) => Some(Geometry { lambda: q, ..geometry }),
+            (Unknown::L2, _) => Some(Geometry { l2: q, ..geometry }),
+            (Unknown::L1, Source::Point { angle, .. }) => Some(Geometry { source: Source::Point { l1: q, angle }, ..geometry }),
+            (Unknown::L1, Source::Plane { .. }) => None,
+        }
+    }
+}
+
+pub struct FringeParams {
+    pub show: bool,
+    pub unknown: Unknown,
+}
+
+impl Default for FringeParams {
+    fn default() -> Self {
+        Self {
+            show: false,
+            unknown: Unknown::Lambda,
+        }
+    }
+}
+
+/// extremum of the measurements matched to the theory
+pub struct Pair {
+    pub kind: FeatureKind,
+    pub u: f64,        // of the theoretical extremum
+    pub x: f64,        // measured
+    pub x_theory: f64, // with the current parameters
+}
+
+/// straight line x = a + b·u through the pairs
+pub struct Line {
+    pub a: f64,
+    pub b: f64,
+    pub sigma_b: f64,
+}
+
+/// extrema of the measured points (x, value) sorted by x: kind, x and value
+pub fn measured_extrema(points: &[(f64, f64)]) -> Vec<(FeatureKind, f64, f64)> {
+    points
+        .windows(3)
+        .filter_map(|w| {
+            let [(x0, y0), (x1, y1), (x2, y2)] = [w[0], w[1], w[2]];
+            // repeated x of the table give no parabola
+            if !(x0 < x1 && x1 < x2) {
+                return None;
+            }
+            let kind = if y1 > y0 && y1 >= y2 {
+                FeatureKind::Max
+            } else if y1 < y0 && y1 <= y2 {
+                FeatureKind::Min
+            } else {
+                return None;
+            };
+            // y = y1 + b·t + a·t², t = x - x1
+            let (d0, d2) = (x0 - x1, x2 - x1);
+            let (s0, s2) = ((y0 - y1) / d0, (y2 - y1) / d2);
+            let a = (s2 - s0) / (d2 - d0);
+            let b = s0 - a * d0;
+            let t = if a != 0. { (-b / (2. * a)).clamp(d0, d2) } else { 0. };
+            Some((kind, x1 + t, y1 + b * t + a * t * t))
+        })
+        .collect()
+}
+
+/// every measured extremum takes the nearest theoretical one (kind, x, u) of its kind,
+/// a theoretical extremum is taken once, by the nearest measured one
+pub fn match_extrema(measured: &[(FeatureKind, f64, f64)], theory: &[(FeatureKind, f64, f64)]) -> Vec<Pair> {
+    // a zero is a minimum for the measurements
+    let same = |a: FeatureKind, b: FeatureKind| {
+        let min = |k: FeatureKind| if k == FeatureKind::Zero { FeatureKind::Min } else { k };
+        min(a) == min(b)
+    };
+
+    let mut taken: Vec<Option<Pair>> = theory.iter().map(|_| None).collect();
+    for &(kind, x, _) in measured {
+        let nearest = theory
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| same(t.0, kind))
+            .min_by(|a, b| (a.1.1 - x).abs().total_cmp(&(b.1.1 - x).abs()));
+        let Some((i, &(_, x_theory, u))) = nearest else {
+            continue;
+        };
+        let closer = taken[i].as_ref().is_none_or(|p| (x - x_theory).abs() < (p.x - x_theory).abs());
+        if closer {
+            taken[i] = Some(Pair { kind, u, x, x_theory });
+        }
+    }
+    taken.into_iter().flatten().collect()
+}
+
+/// least squares line, None for less than three pairs
+pub fn fit_line(pairs: &[Pair]) -> Option<Line> {
+    let n = pairs.len();
+    if n < MIN_PAIRS {
+        return None;
+    }
+    let (u_mean, x_mean) = (
+        pairs.iter().map(|p| p.u).sum::<f64>() / n as f64,
+        pairs.iter().map(|p| p.x).sum::<f64>() / n as f64,
+    );
+    let suu: f64 = pairs.iter().map(|p| (p.u - u_mean).powi(2)).sum();
+    if suu == 0. {
+        return None;
+    }
+    let sux: f64 = pairs.iter().map(|p| (p.u - u_mean) * (p.x - x_mean)).sum();
+    let b = sux / suu;
+    let a = x_mean - b * u_mean;
+    let rest: f64 = pairs.iter().map(|p| (p.x - a - b * p.u).powi(2)).sum();
+    let sigma_b = (rest / (n - 2) as f64 / suu).sqrt();
+    Some(Line { a, b, sigma_b })
+}
+
+/// the unknown q near `current` for which the theory has the slope b of the line
+/// and its error, `slope(q)` is the slope of the theory; None if it is out of reach
+pub fn solve(current: f64, line: &Line, slope: impl Fn(f64) -> f64) -> Option<(f64, f64)> {
+    let gap = |lg: f64| slope(10f64.powf(lg)).abs() - line.b.abs();
+    let (lo, hi) = (current.log10() - SEARCH.log10(), current.log10() + SEARCH.log10());
+    if (gap(lo) < 0.) == (gap(hi) < 0.) {
+        return None;
+    }
+    let q = 10f64.powf(bisect(lo, hi, gap));
+
+    let db = (slope(q * (1. + STEP)).abs() - slope(q * (1. - STEP)).abs()) / (2. * q * STEP);
+    Some((q, line.sigma_b / db.abs()))
+}
+
+/// everything the window shows
+pub struct Fit {
+    pub pairs: Vec<Pair>,
+    pub line: Line,
+    pub value: Option<(Length, Length)>, // the unknown and its error, None if no theory has the slope
+}
+
+/// the unknown from the points of the table and the extrema of the theory,
+/// Err is the reason for the user
+pub fn fit(fz: &Difr, unknown: Unknown) -> Result<Fit, &'static str> {
+    let geometry = fz.geometry();
+    if unknown.trial(geometry, 1.).is_none() {
+        return Err("у плоской волны нет L1");
+    }
+
+    let points: Vec<(f64, f64)> = fz.get_student_points().iter().copied().filter(|&p| p != (0., 0.)).collect();
+    let measured = measured_extrema(&points);
+    if measured.is_empty() {
+        return Err("в таблице нет экстремумов");
+    }
+    // the antenna or the spectrum smooth the fringes and move their extrema
+    let features = match fz.features_received.is_empty() {
+        true => &fz.features,
+        false => &fz.features_received,
+    };
+    let theory: Vec<(FeatureKind, f64, f64)> = features
+        .iter()
+        .filter(|f| matches!(f.kind, FeatureKind::Max | FeatureKind::Min | FeatureKind::Zero))
+        .map(|f| {
+            let x = fz.p_to_x(f.p);
+            (f.kind, x, fz.x_to_edge_u(x))
+        })
+        .collect();
+
+    let pairs = match_extrema(&measured, &theory);
+    let Some(line) = fit_line(&pairs) else {
+        return Err("нужно хотя бы 3 экстремума");
+    };
+    let x_mean = pairs.iter().map(|p| p.x).sum::<f64>() / pairs.len() as f64;
+    let slope = |q: f64| unknown.trial(geometry, q).map_or(f64::NAN, |g| fz.x_per_u(&g, x_mean));
+    let value = solve(unknown.value(fz).cm(), &line, slope)
+        .map(|(q, sigma)| (Length::from_cm(q), Length::from_cm(sigma)));
+    Ok(Fit { pairs, line, value })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::windows::math::worker::Job;
+
+    const LAMBDA: f64 = 3.2; // cm, the one of the measurements
+
+    // the curves for the current parameters, as the worker makes them
+    fn rebuild(fz: &mut Difr) {
+        let curves = fz.compute(&Job::detached()).unwrap();
+        fz.apply(curves);
+    }
+
+    // table of the received |F| at λ = LAMBDA, then the fit from the wrong λ
+    // taken into the parameters until it settles, as the window suggests
+    fn round_trip(aperture: bool) {
+        let mut fz = Difr::default();
+        fz.aperture = aperture;
+        fz.aperture_width = Length::from_cm(8.); // a wide horn moves the extrema
+        fz.lambda = Length::from_cm(LAMBDA);
+        // the default geometry has a few fringes over the whole travel of the edge,
+        // the ruler is read with an error up to 0.2 mm
+        let mut seed = 1u64;
+        *fz.get_student_points() = (0..=400)
+            .map(|i| -20. + 0.1 * i as f64)
+            .map(|x| {
+                seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+                let error = 0.04 * ((seed >> 11) as f64 / (1u64 << 53) as f64 - 0.5);
+                (x + error, fz.get_point_norm_received(fz.x_to_p(x))[1])
+            })
+            .collect();
+
+        fz.lambda = Length::from_cm(2.8);
+        let mut fit_result = None;
+        for _ in 0..3 {
+            rebuild(&mut fz);
+            let fit = fit(&fz, Unknown::Lambda).unwrap();
+            assert!(fit.pairs.len() >= MIN_PAIRS);
+            let (lambda, sigma) = fit.value.unwrap();
+            fz.lambda = lambda;
+            fit_result = Some((lambda.cm(), sigma.cm()));
+        }
+
+        // σ is from the scatter of a few vertices of parabolas, so it is rough
+        let (lambda, sigma) = fit_result.unwrap();
+        assert!((lambda - LAMBDA).abs() <= 3. * sigma, "λ = {lambda} ± {sigma}");
+    }
+
+    #[test]
+    fn lambda_back() {
+        round_trip(false);
+    }
+
+    // the extrema of the antenna are not those of the point probe
+    #[test]
+    fn lambda_back_with_aperture() {
+        round_trip(true);
+    }
+
+    #[test]
+    fn repeated_x() {
+        let points = [(0., 1.), (1., 2.), (1., 2.), (2., 1.), (3., 0.5), (4., 1.)];
+        let extrema = measured_extrema(&points);
+        assert!(extrema.iter().all(|e| e.1.is_finite() && e.2.is_finite()));
+        assert_eq!(extrema.len(), 1);
+        assert_eq!(extrema[0].0, FeatureKind::Min);
+    }
+}
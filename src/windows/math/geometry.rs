@@ -0,0 +1,191 @@
+use std::f64::consts::{PI, TAU};
+
+// Positions of the source and the receiver relative to the screen.
+// The screen is the plane z = 0, its center is x = 0,
+// the receiver is at (receiver, 0, l2). Everything is in cm.
+
+/// where the wave comes from
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Source {
+    // point source at distance l1 from the center of the screen,
+    // the arm is tilted by `angle` (rad) from the axis to the +x side
+    Point { l1: f64, angle: f64 },
+    // plane wave from the same direction (L1 → ∞)
+    Plane { angle: f64 },
+}
+
+/// pattern of the point source
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Beam {
+    Ideal,
+    // horn: amplitude is cos^n of the angle from the axis,
+    // `width` is the full width of the pattern by -3 dB in degrees
+    Horn { width: f64 },
+    // Gaussian beam with the waist `waist` (cm) in the source
+    Gauss { waist: f64 },
+}
+
+impl Beam {
+    // power of cos for the horn
+    #[inline]
+    pub fn horn_n(width: f64) -> f64 {
+        -0.5 * 2f64.ln() / (width.to_radians() / 2.).cos().ln()
+    }
+
+    // far field half angle of divergence of the Gaussian beam (rad)
+    #[inline]
+    pub fn divergence(waist: f64, lambda: f64) -> f64 {
+        (lambda / (PI * waist)).atan()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Geometry {
+    pub source: Source,
+    pub beam: Beam,
+    pub l2: f64,
+    pub lambda: f64,
+    pub receiver: f64, // lateral offset of the receiver
+}
+
+/// incident wave in a point of the screen plane
+pub struct Incident {
+    pub amp: f64,
+    pub path: f64, // phase is -k·path
+    pub cos: f64,  // cos of angle to the normal of the screen
+    pub inv_r: f64, // 1/r of spherical wave, 0 for plane
+}
+
+impl Geometry {
+    #[inline]
+    pub fn new(l1: f64, l2: f64, lambda: f64, plane: bool, angle: f64) -> Self {
+        let source = match plane {
+            true => Source::Plane { angle },
+            false => Source::Point { l1, angle },
+        };
+        Self { source, beam: Beam::Ideal, l2, lambda, receiver: 0. }
+    }
+
+    #[inline]
+    pub fn with_beam(self, beam: Beam) -> Self {
+        Self { beam, ..self }
+    }
+
+    // wave of the point source in the point shifted by (dx, dy, dz) from it
+    // as (amplitude, path)
+    #[inline]
+    fn spherical(&self, angle: f64, dx: f64, dy: f64, dz: f64) -> (f64, f64) {
+        let r = dx.hypot(dy).hypot(dz);
+        let z = dz * angle.cos() - dx * angle.sin(); // along the axis of the beam
+        match self.beam {
+            Beam::Ideal => (1. / r, r),
+            Beam::Horn { width } => ((z / r).max(0.).powf(Beam::horn_n(width)) / r, r),
+            Beam::Gauss { waist } => {
+                let zr = PI * waist * waist / self.lambda;
+                let w = waist * (1. + (z / zr).powi(2)).sqrt();
+                let rho2 = (r * r - z * z).max(0.);
+                let gouy = (z / zr).atan() * self.lambda / TAU;
+                let amp = waist / w * (-rho2 / (w * w)).exp();
+                (amp, z + rho2 * z / (2. * (z * z + zr * zr)) - gouy)
+            }
+        }
+    }
+
+    #[inline]
+    pub fn with_receiver(self, receiver: f64) -> Self {
+        Self { receiver, ..self }
+    }
+
+    /// incident wave in the point (x, y, 0)
+    #[inline]
+    pub fn incident(&self, x: f64, y: f64) -> Incident {
+        match self.source {
+            Source::Point { l1, angle } => {
+                let (xs, zs) = (l1 * angle.sin(), l1 * angle.cos());
+                let r = (x - xs).hypot(y).hypot(zs);
+                let (amp, path) = self.spherical(angle, x - xs, y, zs);
+                Incident { amp, path, cos: zs / r, inv_r: 1. / r }
+            }
+            Source::Plane { angle } => Incident {
+                amp: 1.,
+                path: -x * angle.sin(),
+                cos: angle.cos(),
+                inv_r: 0.,
+            },
+        }
+    }
+
+    /// unobstructed wave in the receiver as (amplitude, path)
+    #[inline]
+    pub fn direct(&self) -> (f64, f64) {
+        match self.source {
+            Source::Point { l1, angle } => {
+                let (xs, zs) = (l1 * angle.sin(), l1 * angle.cos());
+                self.spherical(angle, self.receiver - xs, 0., zs + self.l2)
+            }
+            Source::Plane { angle } => (1., self.l2 * angle.cos() - self.receiver * angle.sin()),
+        }
+    }
+
+    /// direction from the point (x, 0, 0) to the source and distance to it
+    #[inline]
+    pub fn source_dir(&self, x: f64) -> (f64, f64, f64) {
+        match self.source {
+            Source::Point { l1, angle } => {
+                let (dx, dz) = (l1 * angle.sin() - x, -l1 * angle.cos());
+                (dx, dz, dx.hypot(dz))
+            }
+            Source::Plane { angle } => (angle.sin(), -angle.cos(), f64::INFINITY),
+        }
+    }
+
+    /// x where the direct ray to the receiver crosses the screen (geometric shadow)
+    #[inline]
+    pub fn shadow(&self) -> f64 {
+        match self.source {
+            Source::Point { l1, angle } => {
+                let (xs, zs) = (l1 * angle.sin(), l1 * angle.cos());
+                xs + (self.receiver - xs) * zs / (zs + self.l2)
+            }
+            Source::Plane { angle } => self.receiver + self.l2 * angle.tan(),
+        }
+    }
+
+    /// x of the ray from the source through the edge x_e at distance z behind the screen
+    #[inline]
+    pub fn boundary(&self, x_e: f64, z: f64) -> f64 {
+        match self.source {
+            Source::Point { l1, angle } => {
+                let (xs, zs) = (l1 * angle.sin(), l1 * angle.cos());
+                x_e + (x_e - xs) * z / zs
+            }
+            Source::Plane { angle } => x_e - z * angle.tan(),
+        }
+    }
+
+    /// lengths of the direct ray before and after the screen and cos of its angle to the axis
+    #[inline]
+    pub fn ray(&self) -> (f64, f64, f64) {
+        let xc = self.shadow();
+        let r2 = (xc - self.receiver).hypot(self.l2);
+        let r1 = match self.source {
+            Source::Point { .. } => self.source_dir(xc).2,
+            Source::Plane { .. } => f64::INFINITY,
+        };
+        (r1, r2, self.l2 / r2)
+    }
+
+    /// coefficient of Fresnel zones: radius of n-th zone is √(n·b)
+    #[inline]
+    pub fn b(&self) -> f64 {
+        let (r1, r2, _) = self.ray();
+        self.lambda / (1. / r1 + 1. / r2)
+    }
+
+    /// scale of Fresnel parameter u = k·(x - shadow)
+    #[inline]
+    pub fn k(&self) -> f64 {
+        let (_, _, cos) = self.ray();
+        cos * (2. / self.b()).sqrt()
+    }
+}
@@ -0,0 +1,233 @@
+use crate::windows::math::geometry::Geometry;
+use crate::windows::math::transmission::Opening;
+use crate::windows::math::worker::Job;
+use num_complex::Complex64;
+use std::f64::consts::{FRAC_PI_4, PI, TAU};
+use std::thread;
+
+// Direct numerical Kirchhoff integration over the plane of the screen.
+//
+// F = j/λ ∫∫ R/(r·s) · K · e^(-jk(r + s - R)) dx dy,
+// K = (cosθ1 + cosθ2)/2 - j/2k · (cosθ1/r + cosθ2/s),
+// r and s are exact distances from the source and to the receiver,
+// R is the distance between them. A plane wave has 1/r = 0 and R/r = 1.
+// A horn or a Gaussian beam changes only the incident wave in the screen,
+// the result is normalized by the same beam in the receiver.
+// The second part of K is the near field, it is lost in Fresnel–Kirchhoff
+// formula and matters when L1 or L2 is about λ.
+// The infinite plane is cut with a smooth window a few dozens of Fresnel
+// zones away from the direct ray and from the edge, so the lost tail is tiny.
+
+const ZONES: f64 = 20.; // width of window and of the flat part around the ray
+const MARGIN: f64 = 10.; // zones between the farthest edge and the window
+const SAMPLES: f64 = 8.; // steps on the shortest period of the integrand
+
+// path difference to the point of screen at distance rho from the ray,
+// l1 = ∞ for a plane wave
+#[inline]
+fn path_difference(rho: f64, l1: f64, l2: f64) -> f64 {
+    let sagitta = |l: f64| rho * rho / (rho.hypot(l) + l);
+    sagitta(l1) + sagitta(l2)
+}
+
+// radius where the path difference is `target`
+fn radius_for(target: f64, l1: f64, l2: f64) -> f64 {
+    // path_difference(rho) >= rho - min(l1, l2)
+    let (mut lo, mut hi) = (0., target + l1.min(l2));
+    for _ in 0..60 {
+        let mid = (lo + hi) / 2.;
+        if path_difference(mid, l1, l2) < target {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    hi
+}
+
+// smooth window: 1 until r_in, 0 after r_out
+#[inline]
+fn taper(rho: f64, r_in: f64, r_out: f64) -> f64 {
+    if rho <= r_in {
+        1.
+    } else if rho >= r_out {
+        0.
+    } else {
+        0.5 * (1. + (PI * (rho - r_in) / (r_out - r_in)).cos())
+    }
+}
+
+// nodes from -end to end: fine near zero where the amplitude is sharp,
+// but never coarser than `step` where the phase runs fast
+fn grid(end: f64, step: f64, scale: f64) -> Vec<f64> {
+    let mut half = vec![0.];
+    let mut x: f64 = 0.;
+    while x < end {
+        x += step.min(x.hypot(scale) / (2. * SAMPLES));
+        half.push(x.min(end));
+    }
+    half.iter()
+        .rev()
+        .map(|x| -x)
+        .chain(half.iter().skip(1).copied())
+        .collect()
+}
+
+// trapezoid weights of nodes
+fn weights(xs: &[f64]) -> Vec<f64> {
+    let n = xs.len();
+    (0..n)
+        .map(|i| (xs[(i + 1).min(n - 1)] - xs[i.saturating_sub(1)]) / 2.)
+        .collect()
+}
+
+/// diffraction factor for every screen in `screens`, each is the list of its openings (cm),
+/// None if the job is cancelled
+pub fn kirchhoff_curve(
+    screens: &[Vec<Opening>],
+    geometry: &Geometry,
+    job: &Job,
+) -> Option<Vec<Complex64>> {
+    let lambda = geometry.lambda;
+    let (l2, xr) = (geometry.l2, geometry.receiver);
+    let k = TAU / lambda;
+    let half_wave = lambda / 2.;
+    let (amp0, path0) = geometry.direct();
+
+    // the window is centered on the direct ray, along the edge it is set by zones only,
+    // across the edge it must also leave every edge position in the flat part
+    let xc = geometry.shadow();
+    let (r1, r2, cos) = geometry.ray();
+    let edge_max = screens
+        .iter()
+        .flatten()
+        .flat_map(|o| [o.a, o.b])
+        .filter(|x| x.is_finite())
+        .fold(0., |m: f64, x| m.max((x - xc).abs()));
+    let dy_in = ZONES * half_wave;
+    let dx_in = dy_in.max(path_difference(edge_max * cos, r1, r2) + MARGIN * half_wave);
+    let (y_in, y_out) = (radius_for(dy_in, r1, r2), radius_for(dy_in + ZONES * half_wave, r1, r2));
+    let (x_in, x_out) = (
+        radius_for(dx_in, r1, r2) / cos,
+        radius_for(dx_in + ZONES * half_wave, r1, r2) / cos,
+    );
+
+    // the fastest oscillation is in a corner of the area,
+    // near the ray amplitude changes on the scale of L1 and L2
+    let period = [xc - x_out, xc + x_out]
+        .map(|x| {
+            let inc = geometry.incident(x, y_out);
+            let s = (x - xr).hypot(y_out).hypot(l2);
+            lambda / ((1. - inc.cos * inc.cos).sqrt() + (1. - (l2 / s).powi(2)).sqrt())
+        })
+        .into_iter()
+        .fold(f64::INFINITY, f64::min);
+    let step = period / SAMPLES;
+    let xs: Vec<f64> = grid(x_out, step, r1.min(r2)).into_iter().map(|x| x + xc).collect();
+    let ys = grid(y_out, step, r1.min(r2));
+    let wys = weights(&ys);
+
+    // integral over y for every x
+    let strip = |x: f64| -> Complex64 {
+        let wx = taper((x - xc).abs(), x_in, x_out);
+        if wx == 0. {
+            return Complex64::new(0., 0.);
+        }
+        let mut sum = Complex64::new(0., 0.);
+        for (&y, &wy) in ys.iter().zip(wys.iter()) {
+            let w = wx * wy * taper(y.abs(), y_in, y_out);
+            if w == 0. {
+                continue;
+            }
+            let inc = geometry.incident(x, y);
+            let s = (x - xr).hypot(y).hypot(l2);
+            let cos2 = l2 / s;
+            let kernel = Complex64::new((inc.cos + cos2) / 2., -(inc.cos * inc.inv_r + cos2 / s) / (2. * k));
+            let amp = w * inc.amp / (amp0 * s);
+            sum += kernel * Complex64::from_polar(amp, -k * (inc.path + s - path0));
+        }
+        sum
+    };
+
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk = xs.len().div_ceil(threads);
+    // every thread stops on the first strip after the job is cancelled
+    let strips: Vec<Complex64> = thread::scope(|sc| {
+        let handles: Vec<_> = xs
+            .chunks(chunk)
+            .map(|xs| {
+                sc.spawn(|| {
+                    xs.iter()
+                        .map(|&x| job.alive().then(|| strip(x)))
+                        .collect::<Option<Vec<_>>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .collect::<Option<Vec<_>>>()
+    })?
+    .concat();
+
+    // cumulative integral over x (trapezoid), cum[i] is from -inf to xs[i]
+    let mut cum = vec![Complex64::new(0., 0.); xs.len()];
+    for i in 1..xs.len() {
+        cum[i] = cum[i - 1] + (strips[i - 1] + strips[i]) * ((xs[i] - xs[i - 1]) / 2.);
+    }
+
+    let cum_at = |x: f64| -> Complex64 {
+        let i = xs.partition_point(|&v| v < x);
+        if i == 0 {
+            return cum[0];
+        }
+        if i == xs.len() {
+            return cum[xs.len() - 1];
+        }
+        let f = (x - xs[i - 1]) / (xs[i] - xs[i - 1]);
+        cum[i - 1] * (1. - f) + cum[i] * f
+    };
+
+    // j/λ and the phase reference of Difr::compute (φ = π/4 without screens)
+    let norm = Complex64::from_polar(1. / lambda, PI / 2. + FRAC_PI_4);
+
+    // infinite ends take the ends of cum
+    let curve = screens
+        .iter()
+        .map(|openings| openings.iter().map(|o| o.t * (cum_at(o.b) - cum_at(o.a))).sum::<Complex64>())
+        .map(|f| f * norm)
+        .collect();
+    Some(curve)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::windows::math::sommerfeld::edge_factor;
+
+    const LAMBDA: f64 = 3.2;
+
+    // largest distance to the Fresnel knife edge for u in [-3, 3], l1 = l2 = l
+    fn error(l: f64) -> f64 {
+        let us: Vec<f64> = (-6..=6).map(|i| i as f64 / 2.).collect();
+        let x_per_u = (LAMBDA * l / 4.).sqrt();
+        let screens: Vec<Vec<Opening>> = us
+            .iter()
+            .map(|&u| vec![Opening::open(f64::NEG_INFINITY, u * x_per_u)])
+            .collect();
+        let geometry = Geometry::new(l, l, LAMBDA, false, 0.);
+        let curve = kirchhoff_curve(&screens, &geometry, &Job::detached()).unwrap();
+        us.iter()
+            .zip(curve)
+            .map(|(&u, f)| (f - edge_factor(u)).norm())
+            .fold(0., f64::max)
+    }
+
+    // the difference is the obliquity and the near field, both go as λ/L
+    #[test]
+    fn paraxial_is_fresnel() {
+        let (near, far) = (error(100.), error(1600.));
+        assert!(far < 3e-3, "{far}");
+        assert!(far < near / 4., "{near} {far}");
+    }
+}
@@ -1,3 +1,18 @@
 pub mod calc;
 pub mod chart;
-pub mod difr;
\ No newline at end of file
+pub mod circular;
+pub mod difr;
+pub mod features;
+pub mod fringes;
+pub mod fresnel;
+pub mod geometry;
+pub mod kirchhoff;
+pub mod phasors;
+pub mod propagation;
+pub mod pulse;
+pub mod sommerfeld;
+pub mod spectrum;
+pub mod transmission;
+pub mod units;
+pub mod worker;
+pub mod zone_plate;
\ No newline at end of file
@@ -0,0 +1,97 @@
+use crate::windows::math::transmission::{openings_field, Opening};
+use num_complex::Complex64;
+use std::f64::consts::{FRAC_PI_4, PI, SQRT_2};
+
+// Contributions of Fresnel zones to the field in the receiver.
+// Zone n is the ring √((n-1)·b) < ρ < √(n·b) around the direct ray, open it
+// gives 2·(-1)^(n-1) of the free wave. A thin ring inside the zone is weighted
+// by the mean transmission of the screen along it, so a partly closed zone
+// gives a shorter and turned arrow.
+// Strip zones of a straight edge are pieces of the Cornu spiral instead.
+// For an unbounded screen the partial sums swing by half of the last zone,
+// so the rest of the zones is the field minus the drawn arrows.
+// Arrows are in the frame of the plots, the free wave is e^(jπ/4).
+
+const STEPS: usize = 32; // rings over a zone
+
+/// screen as it is seen from the center of zones
+pub enum ZoneScreen<'a> {
+    Strips(&'a [Opening]), // openings over x
+    Round { r: f64, t: Complex64, disk: bool }, // hole (or disk) at x = 0, the rest has t
+}
+
+impl ZoneScreen<'_> {
+    // mean transmission over the circle of radius rho around x = c
+    fn mean(&self, rho: f64, c: f64) -> Complex64 {
+        match self {
+            ZoneScreen::Strips(openings) => {
+                // part of the circle between x = a and x = b
+                let arc = |a: f64, b: f64| {
+                    let cos = |x: f64| ((x - c) / rho).clamp(-1., 1.).acos();
+                    (cos(a.min(b)) - cos(a.max(b))) / PI
+                };
+                openings.iter().map(|o| o.t * arc(o.a, o.b)).sum()
+            }
+            &ZoneScreen::Round { r, t, disk } => {
+                // part of the circle inside the round one
+                let inside = if c == 0. {
+                    if rho < r { 1. } else { 0. }
+                } else {
+                    let q = (r * r - rho * rho - c * c) / (2. * rho * c.abs());
+                    1. - q.clamp(-1., 1.).acos() / PI
+                };
+                let one = Complex64::new(1., 0.);
+                match disk {
+                    false => one * inside + t * (1. - inside),
+                    true => t * inside + one * (1. - inside),
+                }
+            }
+        }
+    }
+}
+
+/// arrows of zones 1..=n around x = c with the coefficient b of zones
+pub fn zone_phasors(screen: &ZoneScreen, c: f64, b: f64, n: usize) -> Vec<Complex64> {
+    let turn = |s: f64| Complex64::from_polar(1., -PI * s);
+    (0..n)
+        .map(|zone| {
+            let sum: Complex64 = (0..STEPS)
+                .map(|i| {
+                    // ρ² in zones
+                    let s0 = zone as f64 + i as f64 / STEPS as f64;
+                    let s1 = s0 + 1. / STEPS as f64;
+                    let rho = ((s0 + s1) / 2. * b).sqrt();
+                    screen.mean(rho, c) * (turn(s0) - turn(s1))
+                })
+                .sum();
+            to_plot_frame(sum)
+        })
+        .collect()
+}
+
+/// field relative to the free wave in the frame of the plots
+#[inline]
+pub fn to_plot_frame(field: Complex64) -> Complex64 {
+    field * Complex64::from_polar(1., FRAC_PI_4)
+}
+
+/// arrows of strip zones 1..=n around x = c: pieces of the Cornu spiral between
+/// u = ±√(2(n-1)) and u = ±√(2n), u = k·(x - c), already in the frame of the plots
+pub fn strip_phasors(openings: &[Opening], c: f64, k: f64, n: usize) -> Vec<Complex64> {
+    let edge = |n: usize| (2. * n as f64).sqrt() / k;
+    (1..=n)
+        .map(|zone| {
+            let (d1, d2) = (edge(zone - 1), edge(zone));
+            let clipped: Vec<Opening> = [(c + d1, c + d2), (c - d2, c - d1)]
+                .into_iter()
+                .flat_map(|(lo, hi)| {
+                    openings.iter().filter_map(move |o| {
+                        let (a, b) = (o.a.max(lo), o.b.min(hi));
+                        (a < b).then_some(Opening { a, b, t: o.t })
+                    })
+                })
+                .collect();
+            openings_field(&clipped, k, c) / SQRT_2
+        })
+        .collect()
+}
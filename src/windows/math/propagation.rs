@@ -0,0 +1,122 @@
+use num_complex::Complex64;
+use rustfft::{Fft, FftPlanner};
+use std::f64::consts::TAU;
+use std::sync::Arc;
+
+// Propagation of a wave through a mask of any shape by FFT.
+// The field after the mask is its transmission times the incident wave,
+// it is decomposed into plane waves, every one gets its phase on the way to the
+// observation plane, and the sum of them is taken back.
+// A point source is replaced by a plane wave with the Fresnel scaling:
+// the picture at L2 is the one of the plane wave at L1·L2/(L1 + L2)
+// magnified by (L1 + L2)/L1, so the diverging beam does not leave the grid.
+// The grid is padded twice, so the copies of the mask do not overlap.
+// Frequencies which phase changes faster than the grid can follow are cut off
+// (band-limited angular spectrum, Matsushima & Shimobaba 2009), otherwise
+// the transfer function is aliased when L2 is large.
+
+/// transfer function of the free space
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Method {
+    AngularSpectrum, // exact for propagating waves, evanescent ones decay
+    Fresnel,         // paraxial
+}
+
+impl Method {
+    pub const ALL: [Method; 2] = [Method::AngularSpectrum, Method::Fresnel];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Method::AngularSpectrum => "угловой спектр",
+            Method::Fresnel => "Френель",
+        }
+    }
+
+    // multiplier of the plane wave with spatial frequency squared f2 (1/cm²)
+    #[inline]
+    fn transfer(&self, f2: f64, lambda: f64, z: f64) -> Complex64 {
+        let k = TAU / lambda;
+        match self {
+            Method::AngularSpectrum => {
+                let q = 1. - lambda * lambda * f2;
+                if q >= 0. {
+                    Complex64::from_polar(1., -k * z * q.sqrt())
+                } else {
+                    Complex64::new((-k * z * (-q).sqrt()).exp(), 0.)
+                }
+            }
+            Method::Fresnel => Complex64::from_polar(1., -k * z + TAU / 2. * lambda * z * f2),
+        }
+    }
+}
+
+// FFT over rows and then over columns of the square m×m
+fn fft2(data: &mut [Complex64], m: usize, fft: &Arc<dyn Fft<f64>>) {
+    fft.process(data);
+    transpose(data, m);
+    fft.process(data);
+    transpose(data, m);
+}
+
+fn transpose(data: &mut [Complex64], m: usize) {
+    for i in 0..m {
+        for j in i + 1..m {
+            data.swap(i * m + j, j * m + i);
+        }
+    }
+}
+
+/// intensity in the plane l2 behind the mask relative to the wave without it
+/// and the size of this picture in cm.
+/// `mask` is n×n transmission by rows, `side` is its size in cm,
+/// `l1` is the distance to the point source, None for a plane wave.
+pub fn propagate(
+    mask: &[f32],
+    n: usize,
+    side: f64,
+    lambda: f64,
+    l1: Option<f64>,
+    l2: f64,
+    method: Method,
+) -> (Vec<f64>, f64) {
+    let m = 2 * n;
+    let dx = side / n as f64;
+    let (z, scale) = match l1 {
+        Some(l1) => (l1 * l2 / (l1 + l2), (l1 + l2) / l1),
+        None => (l2, 1.),
+    };
+
+    let mut field = vec![Complex64::new(0., 0.); m * m];
+    for i in 0..n {
+        for j in 0..n {
+            field[(i + n / 2) * m + j + n / 2] = Complex64::new(mask[i * n + j] as f64, 0.);
+        }
+    }
+
+    let mut planner = FftPlanner::new();
+    fft2(&mut field, m, &planner.plan_fft_forward(m));
+
+    let df = 1. / (m as f64 * dx);
+    // frequencies of FFT: 0, 1, ..., m/2 - 1, -m/2, ..., -1
+    let freq = |i: usize| if i < m / 2 { i as f64 } else { i as f64 - m as f64 } * df;
+    let f_max = 1. / (lambda * (2. * df * z).hypot(1.));
+    for i in 0..m {
+        for j in 0..m {
+            let (fy, fx) = (freq(i), freq(j));
+            field[i * m + j] *= if fx.abs() < f_max && fy.abs() < f_max {
+                method.transfer(fx * fx + fy * fy, lambda, z)
+            } else {
+                Complex64::new(0., 0.)
+            };
+        }
+    }
+
+    fft2(&mut field, m, &planner.plan_fft_inverse(m));
+
+    let norm = 1. / (m * m) as f64;
+    let intensity = (0..n)
+        .flat_map(|i| (0..n).map(move |j| (i, j)))
+        .map(|(i, j)| (field[(i + n / 2) * m + j + n / 2] * norm).norm_sqr())
+        .collect();
+    (intensity, side * scale)
+}
@@ -0,0 +1,112 @@
+use crate::windows::math::units::{Frequency, Length};
+use num_complex::Complex64;
+use rustfft::FftPlanner;
+use std::f64::consts::FRAC_PI_4;
+
+// Pulse behind the screens: the spectrum of the pulse is multiplied by the
+// diffraction factor of every frequency and returned to time by inverse FFT.
+// Time is counted from the arrival of the direct wave.
+
+const N: usize = 1024; // points of FFT
+const DF: f64 = 0.2; // step of frequency in ГГц, the window is 1/DF = 5 нс
+const MIN_SPECTRUM: f64 = 1e-4; // weaker frequencies are not calculated
+
+/// shape of the pulse
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Pulse {
+    Gauss,     // sine of frequency f in a gaussian envelope
+    Monocycle, // derivative of gaussian, its spectrum has maximum at f
+}
+
+impl Pulse {
+    pub const ALL: [Pulse; 2] = [Pulse::Gauss, Pulse::Monocycle];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Pulse::Gauss => "радиоимпульс",
+            Pulse::Monocycle => "моноцикл",
+        }
+    }
+
+    // amplitude spectrum, f0 is the centre, width is the band by half of amplitude (ГГц)
+    #[inline]
+    fn spectrum(&self, f: f64, f0: f64, width: f64) -> f64 {
+        match self {
+            Pulse::Gauss => {
+                let sigma = width / (2. * (2. * 2f64.ln()).sqrt());
+                (-(f - f0).powi(2) / (2. * sigma * sigma)).exp()
+            }
+            Pulse::Monocycle => {
+                let x = f / f0;
+                x * ((1. - x * x) / 2.).exp()
+            }
+        }
+    }
+}
+
+/// waveforms without screens and behind them as [t (нс), value], both are
+/// normalized by the peak of the first one, f0 and width are in ГГц. `factor(lambda)`
+/// is the diffraction factor for the wave length in the phase convention of `Difr`.
+pub fn waveforms(
+    pulse: Pulse,
+    f0: f64,
+    width: f64,
+    factor: impl Fn(Length) -> Complex64,
+) -> (Vec<[f64; 2]>, Vec<[f64; 2]>) {
+    let mut free = vec![Complex64::new(0., 0.); N];
+    let mut behind = vec![Complex64::new(0., 0.); N];
+
+    for i in 1..N / 2 {
+        let f = i as f64 * DF;
+        let s = pulse.spectrum(f, f0, width);
+        if s < MIN_SPECTRUM {
+            continue;
+        }
+        // the factor of Difr is turned by π/4, without screens it is e^(jπ/4)
+        let fz = factor(Frequency::from_ghz(f).wavelength()) * Complex64::from_polar(1., -FRAC_PI_4);
+
+        free[i] = Complex64::new(s, 0.);
+        behind[i] = fz * s;
+        // the signal is real
+        free[N - i] = free[i].conj();
+        behind[N - i] = behind[i].conj();
+    }
+
+    let ifft = FftPlanner::new().plan_fft_inverse(N);
+    ifft.process(&mut free);
+    ifft.process(&mut behind);
+
+    let max = free.iter().fold(0., |m: f64, c| m.max(c.re.abs()));
+    let dt = 1. / (N as f64 * DF);
+    // zero time in the middle
+    let line = |v: &[Complex64]| -> Vec<[f64; 2]> {
+        (0..N)
+            .map(|j| {
+                let t = (j as f64 - (N / 2) as f64) * dt;
+                [t, v[(j + N / 2) % N].re / max]
+            })
+            .collect()
+    };
+    (line(&free), line(&behind))
+}
+
+// parameters for the pulse window
+pub struct PulseParams {
+    pub show: bool,
+    pub pulse: Pulse,
+    pub width: Frequency,
+    pub animate: bool,
+    pub speed: Length, // per second, the edge or the receiver is moved with it
+}
+
+impl Default for PulseParams {
+    fn default() -> Self {
+        Self {
+            show: false,
+            pulse: Pulse::Gauss,
+            width: Frequency::from_ghz(2.),
+            animate: false,
+            speed: Length::from_cm(2.),
+        }
+    }
+}
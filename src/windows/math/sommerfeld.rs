@@ -0,0 +1,103 @@
+use crate::windows::math::fresnel::fresnl;
+use crate::windows::math::geometry::Geometry;
+use num_complex::Complex64;
+use std::f64::consts::{FRAC_PI_2, SQRT_2, TAU};
+
+// Sommerfeld's exact solution for a perfectly conducting half-plane.
+//
+// The result is written in the same form as `Difr::compute`:
+// F = f(u_i) ∓ e^(jπ/2·(u_r² - u_i²))·f(u_r), where f(u) is the
+// Fresnel-integral factor of one screen. The first term is the Fresnel
+// answer itself, the second one is the wave "reflected" by the screen.
+// For a spherical wave the distance parameter L = R1·R2/(R1+R2) is used,
+// for a plane wave (R1 → ∞) the solution is exact.
+
+/// polarisation of the incident wave relative to the edge
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Polarisation {
+    Te, // E parallel to the edge, E = 0 on the screen
+    Tm, // H parallel to the edge, dE/dn = 0 on the screen
+}
+
+// factor of one screen, the same as in Difr::compute
+#[inline]
+pub fn edge_factor(u: f64) -> Complex64 {
+    let (c, s) = fresnl(u);
+    Complex64::new(c + 0.5, s + 0.5) / SQRT_2
+}
+
+// angle of vector from the edge, counted from the screen (+x) in [0, 2π)
+#[inline]
+fn screen_angle(dx: f64, dz: f64) -> f64 {
+    dz.atan2(dx).rem_euclid(TAU)
+}
+
+/// diffraction factor of a half-plane x > x_e (z = 0), everything in cm
+pub fn half_plane(x_e: f64, geometry: &Geometry, pol: Polarisation) -> Complex64 {
+    let (sx, sz, r1) = geometry.source_dir(x_e);
+    let r2 = (x_e - geometry.receiver).hypot(geometry.l2);
+
+    let phi0 = screen_angle(sx, sz); // direction to the source
+    let phi = screen_angle(geometry.receiver - x_e, geometry.l2); // direction to the receiver
+
+    // distance parameter, L = R2 for a plane wave
+    let l = 1. / (1. / r1 + 1. / r2);
+    let k = (8. * l / geometry.lambda).sqrt();
+
+    let u_i = k * ((phi - phi0) / 2.).cos();
+    let u_r = k * ((phi + phi0) / 2.).cos();
+
+    let reflected = Complex64::from_polar(1., FRAC_PI_2 * (u_r * u_r - u_i * u_i)) * edge_factor(u_r);
+
+    match pol {
+        Polarisation::Te => edge_factor(u_i) - reflected,
+        Polarisation::Tm => edge_factor(u_i) + reflected,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAMBDA: f64 = 3.2;
+
+    // u of the knife edge by Fresnel for l1 = l2 = l, u > 0 is the lit side
+    fn fresnel_u(x_e: f64, l: f64) -> f64 {
+        x_e * (4. / (LAMBDA * l)).sqrt()
+    }
+
+    fn edge(u: f64, l: f64, pol: Polarisation) -> Complex64 {
+        let x_e = u / fresnel_u(1., l);
+        half_plane(x_e, &Geometry::new(l, l, LAMBDA, false, 0.), pol)
+    }
+
+    // the reflected wave is about 1/(π·u_r) with u_r ≈ -√(8L/λ),
+    // so far from the edge both polarisations give the Fresnel curve
+    #[test]
+    fn paraxial_is_fresnel() {
+        for i in -8..=8 {
+            let u = i as f64 / 2.;
+            let f = edge_factor(u);
+            for pol in [Polarisation::Te, Polarisation::Tm] {
+                let d = (edge(u, 2000., pol) - f).norm();
+                assert!(d < 1e-2, "{pol:?} u = {u}: {d}");
+            }
+        }
+    }
+
+    #[test]
+    fn te_meets_tm() {
+        let split = |l: f64| {
+            (-8..=8)
+                .map(|i| {
+                    let u = i as f64 / 2.;
+                    (edge(u, l, Polarisation::Te) - edge(u, l, Polarisation::Tm)).norm()
+                })
+                .fold(0., f64::max)
+        };
+        let (near, far) = (split(2000.), split(8000.));
+        assert!(near < 2e-2, "{near}");
+        // 1/√L: four times farther is half the difference
+        assert!((far / near - 0.5).abs() < 0.05, "{near} {far}");
+    }
+}
@@ -0,0 +1,41 @@
+use crate::windows::math::units::{Frequency, Length};
+
+const BAND_N: usize = 31; // frequencies in the band
+const MIN_FREQ: Frequency = Frequency::from_ghz(0.1);
+
+/// spectrum of the source instead of the single `freq`
+#[derive(PartialEq, Clone, Debug)]
+pub enum Spectrum {
+    Mono,
+    // flat band of `width` around the main frequency, like a swept source
+    Band { width: Frequency },
+    // separate lines as (frequency, relative power)
+    Lines(Vec<(Frequency, f32)>),
+}
+
+impl Spectrum {
+    /// wavelengths with weights of power, the weights sum to 1
+    pub fn samples(&self, freq: Frequency) -> Vec<(Length, f64)> {
+        let lines: Vec<(Frequency, f32)> = match self {
+            Spectrum::Mono => vec![(freq, 1.)],
+            Spectrum::Band { width } => (0..BAND_N)
+                .map(|i| {
+                    let t = (i as f64 + 0.5) / BAND_N as f64 - 0.5;
+                    (Frequency::from_ghz(freq.ghz() + width.ghz() * t), 1.)
+                })
+                .collect(),
+            Spectrum::Lines(lines) => lines.clone(),
+        };
+
+        let lines: Vec<(Frequency, f32)> = lines
+            .into_iter()
+            .filter(|&(f, p)| f >= MIN_FREQ && p > 0.)
+            .collect();
+        let total: f32 = lines.iter().map(|&(_, p)| p).sum();
+
+        lines
+            .into_iter()
+            .map(|(f, p)| (f.wavelength(), (p / total) as f64))
+            .collect()
+    }
+}
@@ -0,0 +1,215 @@
+use crate::windows::math::fresnel::fresnl;
+use crate::windows::math::units::Length;
+use num_complex::Complex64;
+use std::f64::consts::PI;
+
+// Screen as a list of open intervals of x, each with its complex transmission.
+// Every interval gives the piece of the Cornu spiral between its ends,
+// t·(F(u_b) - F(u_a)), F = C + jS, and the field is the sum of pieces.
+// One screen is (-∞, x), two screens are (-x, x), both with t = 1.
+// Overlapping intervals add up.
+// Far from the screen the quadratic phase over the openings is negligible and
+// the sum turns into the Fraunhofer formula: sinc of a slit times the array factor.
+
+/// interval of the editor, infinite ends are allowed
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct Interval {
+    pub a: Length,
+    pub b: Length,
+    pub amp: f32,   // |t|, 0 is opaque, 1 is open
+    pub phase: f32, // arg t in degrees
+}
+
+impl Interval {
+    pub fn new(a: Length, b: Length) -> Self {
+        Self {
+            a,
+            b,
+            amp: 1.,
+            phase: 0.,
+        }
+    }
+
+    #[inline]
+    pub fn t(&self) -> Complex64 {
+        Complex64::from_polar(self.amp as f64, (self.phase as f64).to_radians())
+    }
+
+    // the same interval moved by x
+    #[inline]
+    pub fn opening(&self, x: f64) -> Opening {
+        Opening {
+            a: self.a.cm() + x,
+            b: self.b.cm() + x,
+            t: self.t(),
+        }
+    }
+}
+
+/// interval of the screen in f64 at its current position
+#[derive(Copy, Clone, Debug)]
+pub struct Opening {
+    pub a: f64,
+    pub b: f64,
+    pub t: Complex64,
+}
+
+impl Opening {
+    #[inline]
+    pub fn open(a: f64, b: f64) -> Self {
+        Self {
+            a,
+            b,
+            t: Complex64::new(1., 0.),
+        }
+    }
+}
+
+/// F(u) = C(u) + jS(u) with limits ±(0.5 + 0.5j) at infinity
+#[inline]
+pub fn fresnel_end(u: f64) -> Complex64 {
+    if u.is_infinite() {
+        return Complex64::new(0.5, 0.5) * u.signum();
+    }
+    let (c, s) = fresnl(u);
+    Complex64::new(c, s)
+}
+
+/// sum of pieces of the spiral, u = k·(x - shadow)
+pub fn openings_field(openings: &[Opening], k: f64, shadow: f64) -> Complex64 {
+    openings
+        .iter()
+        .map(|o| o.t * (fresnel_end(k * (o.b - shadow)) - fresnel_end(k * (o.a - shadow))))
+        .sum()
+}
+
+/// parts of [-x_max, x_max] which are not covered by any opening
+pub fn opaque_parts(openings: &[Opening], x_max: f64) -> Vec<(f64, f64)> {
+    let mut sorted: Vec<(f64, f64)> = openings.iter().map(|o| (o.a.min(o.b), o.a.max(o.b))).collect();
+    sorted.sort_by(|p, q| p.0.total_cmp(&q.0));
+
+    let mut parts = Vec::new();
+    let mut from = -x_max;
+    for (a, b) in sorted {
+        if a > from {
+            parts.push((from, a.min(x_max)));
+        }
+        from = from.max(b);
+        if from >= x_max {
+            return parts;
+        }
+    }
+    parts.push((from, x_max));
+    parts
+}
+
+/// far field of finite openings centered at `center`: the quadratic phase inside
+/// them is dropped, so only the direction to the receiver matters (Fraunhofer)
+pub fn far_field(openings: &[Opening], k: f64, shadow: f64, center: f64) -> Complex64 {
+    let u0 = k * (center - shadow);
+    let slope = PI * u0; // phase of e^(jπ·u0·δ) per unit of δ
+    let piece = |a: f64, b: f64| -> Complex64 {
+        if slope.abs() < 1e-12 {
+            return Complex64::new(b - a, 0.);
+        }
+        (Complex64::from_polar(1., slope * b) - Complex64::from_polar(1., slope * a))
+            / Complex64::new(0., slope)
+    };
+    let sum: Complex64 = openings
+        .iter()
+        .map(|o| o.t * piece(k * (o.a - center), k * (o.b - center)))
+        .sum();
+    sum * Complex64::from_polar(1., PI * u0 * u0 / 2.)
+}
+
+/// N equal slits with the period, centered at the edge position
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct Grating {
+    pub n: usize,
+    pub width: Length,  // of a slit
+    pub period: Length, // between centers of slits
+}
+
+impl Grating {
+    pub fn openings(&self, x: f64) -> Vec<Opening> {
+        let (w, d) = (self.width.cm(), self.period.cm());
+        (0..self.n)
+            .map(|i| {
+                let c = x + (i as f64 - (self.n - 1) as f64 / 2.) * d;
+                Opening::open(c - w / 2., c + w / 2.)
+            })
+            .collect()
+    }
+
+    // size of the whole grating
+    #[inline]
+    pub fn size(&self) -> f64 {
+        (self.n - 1) as f64 * self.period.cm() + self.width.cm()
+    }
+}
+
+impl Default for Grating {
+    fn default() -> Self {
+        Self {
+            n: 5,
+            width: Length::from_cm(1.),
+            period: Length::from_cm(3.),
+        }
+    }
+}
+
+/// ready screens of the editor
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Preset {
+    Slits,
+    Grating,
+    Strip,
+    HalfTransparent,
+    PhaseStep,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 5] = [
+        Preset::Slits,
+        Preset::Grating,
+        Preset::Strip,
+        Preset::HalfTransparent,
+        Preset::PhaseStep,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Preset::Slits => "две щели",
+            Preset::Grating => "решётка",
+            Preset::Strip => "полоса",
+            Preset::HalfTransparent => "полупрозрачный экран",
+            Preset::PhaseStep => "фазовая ступень",
+        }
+    }
+
+    pub fn intervals(&self) -> Vec<Interval> {
+        // ends in cm
+        let new = |a: f64, b: f64| Interval::new(Length::from_cm(a), Length::from_cm(b));
+        match self {
+            Preset::Slits => vec![new(-6., -2.), new(2., 6.)],
+            Preset::Grating => (-3..=3)
+                .map(|i| new(i as f64 * 4. - 1., i as f64 * 4. + 1.))
+                .collect(),
+            Preset::Strip => vec![new(f64::NEG_INFINITY, -3.), new(3., f64::INFINITY)],
+            Preset::HalfTransparent => vec![
+                new(f64::NEG_INFINITY, 0.),
+                Interval {
+                    amp: 0.5,
+                    ..new(0., f64::INFINITY)
+                },
+            ],
+            Preset::PhaseStep => vec![
+                new(f64::NEG_INFINITY, 0.),
+                Interval {
+                    phase: 180.,
+                    ..new(0., f64::INFINITY)
+                },
+            ],
+        }
+    }
+}
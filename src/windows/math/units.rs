@@ -0,0 +1,123 @@
+// Physical quantities of the model, f64 inside: lengths in cm, frequencies in GHz.
+// The physics core (Geometry and the rest) takes plain f64 in these units and
+// gets them only through `cm()` and `ghz()`, the panel shows them in the unit picked by the user.
+
+/// speed of light in cm·GHz
+pub const SPEED_OF_LIGHT: f64 = 29.979_245_8;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct Length(f64);
+
+impl Length {
+    #[inline]
+    pub const fn from_cm(cm: f64) -> Self {
+        Self(cm)
+    }
+
+    #[inline]
+    pub fn from_unit(value: f64, unit: LengthUnit) -> Self {
+        Self(value * unit.in_cm())
+    }
+
+    #[inline]
+    pub fn cm(self) -> f64 {
+        self.0
+    }
+
+    #[inline]
+    pub fn in_unit(self, unit: LengthUnit) -> f64 {
+        self.0 / unit.in_cm()
+    }
+
+    // frequency of the wave of this length
+    #[inline]
+    pub fn frequency(self) -> Frequency {
+        Frequency(SPEED_OF_LIGHT / self.0)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct Frequency(f64);
+
+impl Frequency {
+    #[inline]
+    pub const fn from_ghz(ghz: f64) -> Self {
+        Self(ghz)
+    }
+
+    #[inline]
+    pub fn from_unit(value: f64, unit: FreqUnit) -> Self {
+        Self(value * unit.in_ghz())
+    }
+
+    #[inline]
+    pub fn ghz(self) -> f64 {
+        self.0
+    }
+
+    #[inline]
+    pub fn in_unit(self, unit: FreqUnit) -> f64 {
+        self.0 / unit.in_ghz()
+    }
+
+    // length of the wave of this frequency
+    #[inline]
+    pub fn wavelength(self) -> Length {
+        Length(SPEED_OF_LIGHT / self.0)
+    }
+}
+
+#[derive(PartialEq, Copy, Clone, Debug, Default)]
+pub enum LengthUnit {
+    Mm,
+    #[default]
+    Cm,
+    M,
+}
+
+impl LengthUnit {
+    pub const ALL: [LengthUnit; 3] = [LengthUnit::Mm, LengthUnit::Cm, LengthUnit::M];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LengthUnit::Mm => "мм",
+            LengthUnit::Cm => "см",
+            LengthUnit::M => "м",
+        }
+    }
+
+    #[inline]
+    fn in_cm(&self) -> f64 {
+        match self {
+            LengthUnit::Mm => 0.1,
+            LengthUnit::Cm => 1.,
+            LengthUnit::M => 100.,
+        }
+    }
+}
+
+#[derive(PartialEq, Copy, Clone, Debug, Default)]
+pub enum FreqUnit {
+    Mhz,
+    #[default]
+    Ghz,
+}
+
+impl FreqUnit {
+    pub const ALL: [FreqUnit; 2] = [FreqUnit::Mhz, FreqUnit::Ghz];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FreqUnit::Mhz => "МГц",
+            FreqUnit::Ghz => "ГГц",
+        }
+    }
+
+    #[inline]
+    fn in_ghz(&self) -> f64 {
+        match self {
+            FreqUnit::Mhz => 1e-3,
+            FreqUnit::Ghz => 1.,
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+
+// Computations out of the UI thread.
+// Every job gets the number of its generation, a newer job makes the older ones
+// stale: they see it in `alive` between their steps and stop, and a queued stale
+// job is not started at all. Only the result of the newest job is taken.
+// A panic in a task is caught, the job ends without a result and is marked as failed.
+
+const PROGRESS_SCALE: f64 = 1000.;
+
+/// what the running computation knows about itself
+pub struct Job {
+    generation: u64,
+    latest: Arc<AtomicU64>,
+    progress: Arc<AtomicU32>,
+}
+
+impl Job {
+    // false if a newer job is submitted, the result is not needed anymore
+    #[inline]
+    pub fn alive(&self) -> bool {
+        self.latest.load(Ordering::Relaxed) == self.generation
+    }
+
+    // part of the work which is done, from 0 to 1
+    #[inline]
+    pub fn report(&self, part: f64) {
+        if self.alive() {
+            let value = (part.clamp(0., 1.) * PROGRESS_SCALE) as u32;
+            self.progress.store(value, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+impl Job {
+    // job which is never cancelled, for computations in tests
+    pub fn detached() -> Self {
+        Self {
+            generation: 0,
+            latest: Arc::new(AtomicU64::new(0)),
+            progress: Arc::new(AtomicU32::new(0)),
+        }
+    }
+}
+
+type Task<T> = Box<dyn FnOnce(&Job) -> Option<T> + Send>;
+type Queued<T> = (Job, Task<T>);
+
+// result of a job, None if the task has panicked
+type Outcome<T> = (u64, Option<T>);
+
+/// thread which does the jobs one by one and gives back their results
+pub struct Worker<T> {
+    tasks: Sender<Queued<T>>,
+    results: Receiver<Outcome<T>>,
+    latest: Arc<AtomicU64>,
+    progress: Arc<AtomicU32>,
+    done: u64, // generation of the last taken result
+    failed: bool, // the newest job has ended with a panic
+}
+
+impl<T: Send + 'static> Worker<T> {
+    pub fn new() -> Self {
+        let (tasks, results) = Self::spawn();
+        Self {
+            tasks,
+            results,
+            latest: Arc::new(AtomicU64::new(0)),
+            progress: Arc::new(AtomicU32::new(0)),
+            done: 0,
+            failed: false,
+        }
+    }
+
+    // the thread ends when the worker is dropped and the channel is closed
+    fn spawn() -> (Sender<Queued<T>>, Receiver<Outcome<T>>) {
+        let (tasks, tasks_rx) = channel::<Queued<T>>();
+        let (results_tx, results) = channel();
+        thread::spawn(move || {
+            for (job, task) in tasks_rx {
+                if !job.alive() {
+                    continue;
+                }
+                let result = match panic::catch_unwind(AssertUnwindSafe(|| task(&job))) {
+                    Ok(None) => continue, // cancelled
+                    Ok(Some(result)) => Some(result),
+                    Err(_) => None,
+                };
+                if job.alive() && results_tx.send((job.generation, result)).is_err() {
+                    return;
+                }
+            }
+        });
+        (tasks, results)
+    }
+
+    // new job, the older ones are cancelled
+    pub fn submit(&mut self, task: impl FnOnce(&Job) -> Option<T> + Send + 'static) {
+        let generation = self.latest.fetch_add(1, Ordering::Relaxed) + 1;
+        self.progress.store(0, Ordering::Relaxed);
+        let job = Job {
+            generation,
+            latest: self.latest.clone(),
+            progress: self.progress.clone(),
+        };
+        self.failed = false;
+        // panics are caught, but if the thread is gone anyway it is started again
+        if let Err(lost) = self.tasks.send((job, Box::new(task))) {
+            (self.tasks, self.results) = Self::spawn();
+            let _ = self.tasks.send(lost.0);
+        }
+    }
+
+    // result of the newest job if it is ready, never waits
+    pub fn poll(&mut self) -> Option<T> {
+        let latest = self.latest.load(Ordering::Relaxed);
+        let mut newest = None;
+        loop {
+            match self.results.try_recv() {
+                Ok(outcome) if outcome.0 == latest => newest = Some(outcome),
+                Ok(_) => {}
+                Err(TryRecvError::Empty) => break,
+                // nobody will finish the job, it is not waited for
+                Err(TryRecvError::Disconnected) => {
+                    newest = newest.or(Some((latest, None)));
+                    break;
+                }
+            }
+        }
+
+        let (generation, result) = newest?;
+        self.done = generation;
+        self.failed = result.is_none();
+        result
+    }
+
+    // the newest job is not done yet
+    #[inline]
+    pub fn busy(&self) -> bool {
+        self.done != self.latest.load(Ordering::Relaxed)
+    }
+
+    // the newest job has panicked, it has no result
+    #[inline]
+    pub fn failed(&self) -> bool {
+        self.failed
+    }
+
+    #[inline]
+    pub fn progress(&self) -> f32 {
+        (self.progress.load(Ordering::Relaxed) as f64 / PROGRESS_SCALE) as f32
+    }
+}
+
+impl<T: Send + 'static> Default for Worker<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    // waits for the newest job, the worker is only polled by the UI
+    fn wait(worker: &mut Worker<u32>) -> Option<u32> {
+        for _ in 0..1000 {
+            if let Some(result) = worker.poll() {
+                return Some(result);
+            }
+            if !worker.busy() {
+                return None;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        panic!("the job is not done");
+    }
+
+    #[test]
+    fn panic_in_task() {
+        let mut worker = Worker::new();
+        worker.submit(|_| panic!("broken task"));
+        assert_eq!(wait(&mut worker), None);
+        assert!(worker.failed() && !worker.busy());
+
+        // the next job is done as usual
+        worker.submit(|_| Some(7));
+        assert_eq!(wait(&mut worker), Some(7));
+        assert!(!worker.failed() && !worker.busy());
+    }
+
+    #[test]
+    fn superseded_job() {
+        let mut worker = Worker::new();
+        let (release, gate) = std::sync::mpsc::channel::<()>();
+        // the old job does not look at `alive` and finishes after the new one is submitted
+        worker.submit(move |_| {
+            gate.recv().unwrap();
+            Some(1)
+        });
+        worker.submit(|_| Some(2));
+        release.send(()).unwrap();
+
+        assert_eq!(wait(&mut worker), Some(2));
+        thread::sleep(Duration::from_millis(10));
+        assert_eq!(worker.poll(), None);
+        assert!(!worker.busy());
+    }
+}
@@ -0,0 +1,169 @@
+use num_complex::Complex64;
+use std::f64::consts::TAU;
+use std::fmt::Write;
+
+// Fresnel zone plate for the source at L1 (or a plane wave) and the focus at L2.
+// The boundary of zone n is where the path through the plate is longer than
+// the straight one by nλ/2, paraxially r_n = √(n·b) as in get_fresnel_zones.
+// On the axis a ring of zones r_a..r_b gives e^(-jkΔ(r_a)) - e^(-jkΔ(r_b)),
+// Δ is the path difference to the point z, so the field is a sum of rings.
+// The sheet around the last zone is opaque.
+
+const MARGIN: f64 = 1.; // width of the sheet around the last zone, cm
+
+/// what is done with every second zone
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Plate {
+    OddBlocked,  // center is closed
+    EvenBlocked, // center is open
+    Phase,       // even zones turn the phase by π
+}
+
+impl Plate {
+    pub const ALL: [Plate; 3] = [Plate::OddBlocked, Plate::EvenBlocked, Plate::Phase];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Plate::OddBlocked => "закрыты нечётные",
+            Plate::EvenBlocked => "закрыты чётные",
+            Plate::Phase => "фазовая",
+        }
+    }
+
+    // transmission of zone n, from 1
+    #[inline]
+    pub fn t(&self, n: usize) -> f64 {
+        match (self, n % 2 == 1) {
+            (Plate::OddBlocked, true) | (Plate::EvenBlocked, false) => 0.,
+            (Plate::Phase, false) => -1.,
+            _ => 1.,
+        }
+    }
+}
+
+/// settings of the window of zone plate
+pub struct ZonePlateParams {
+    pub show: bool,
+    pub plate: Plate,
+    pub zones: usize,
+    pub exact: bool, // radii by the exact path, not by √(n·b)
+    pub path: String, // where to save the drawing, extension is set by the format
+    pub message: Option<String>, // result of the last saving
+}
+
+impl Default for ZonePlateParams {
+    fn default() -> Self {
+        Self {
+            show: false,
+            plate: Plate::OddBlocked,
+            zones: 10,
+            exact: true,
+            path: "zone_plate".to_string(),
+            message: None,
+        }
+    }
+}
+
+// path difference via the point of the plate at radius rho, l1 = None for a plane wave
+#[inline]
+fn path_difference(rho: f64, l1: Option<f64>, z: f64) -> f64 {
+    let sagitta = |l: f64| rho * rho / (rho.hypot(l) + l);
+    l1.map_or(0., sagitta) + sagitta(z)
+}
+
+/// outer radii of zones 1..=n in cm
+pub fn radii(l1: Option<f64>, l2: f64, lambda: f64, n: usize, exact: bool) -> Vec<f64> {
+    let b = lambda / (l1.map_or(0., |l1| 1. / l1) + 1. / l2);
+    (1..=n)
+        .map(|i| {
+            let r = (i as f64 * b).sqrt();
+            if !exact {
+                return r;
+            }
+            // path difference grows with the radius and is more than the paraxial one
+            let target = i as f64 * lambda / 2.;
+            let (mut lo, mut hi) = (0., r);
+            while path_difference(hi, l1, l2) < target {
+                hi *= 2.;
+            }
+            for _ in 0..60 {
+                let mid = (lo + hi) / 2.;
+                if path_difference(mid, l1, l2) < target {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            hi
+        })
+        .collect()
+}
+
+/// intensity on the axis at distance z relative to the wave without the plate
+pub fn on_axis(radii: &[f64], plate: Plate, l1: Option<f64>, lambda: f64, z: f64) -> f64 {
+    let k = TAU / lambda;
+    let ring = |r: f64| Complex64::from_polar(1., -k * path_difference(r, l1, z));
+
+    let mut inner = Complex64::new(1., 0.);
+    let mut sum = Complex64::new(0., 0.);
+    for (i, &r) in radii.iter().enumerate() {
+        let outer = ring(r);
+        sum += (inner - outer) * plate.t(i + 1);
+        inner = outer;
+    }
+    sum.norm_sqr()
+}
+
+/// drawing at 1:1 in mm: black is metal, grey is the dielectric of a phase plate
+pub fn svg(radii: &[f64], plate: Plate) -> String {
+    let edge = radii.last().copied().unwrap_or(0.) + MARGIN;
+    let size = 20. * edge; // mm
+    let c = size / 2.;
+
+    // ring between two radii in mm, the hole is cut by evenodd
+    let ring = |r_in: f64, r_out: f64, fill: &str| {
+        let circle = |r: f64| format!("M {:.3} {c:.3} a {r:.3} {r:.3} 0 1 0 {:.3} 0 a {r:.3} {r:.3} 0 1 0 {:.3} 0 Z ", c - r, 2. * r, -2. * r);
+        let mut d = circle(r_out * 10.);
+        if r_in > 0. {
+            d += &circle(r_in * 10.);
+        }
+        format!("  <path d=\"{d}\" fill=\"{fill}\" fill-rule=\"evenodd\" stroke=\"none\"/>\n")
+    };
+
+    let mut out = String::new();
+    let _ = writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+    let _ = writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{size:.3}mm\" height=\"{size:.3}mm\" viewBox=\"0 0 {size:.3} {size:.3}\">"
+    );
+    out += &ring(edge - MARGIN, edge, "black");
+    let mut r_in = 0.;
+    for (i, &r) in radii.iter().enumerate() {
+        let t = plate.t(i + 1);
+        if t == 0. {
+            out += &ring(r_in, r, "black");
+        } else if t < 0. {
+            out += &ring(r_in, r, "grey");
+        }
+        r_in = r;
+    }
+    let _ = writeln!(out, "</svg>");
+    out
+}
+
+/// circles of all zone boundaries and of the sheet in mm, layer ZONES and OUTLINE
+pub fn dxf(radii: &[f64]) -> String {
+    let edge = radii.last().copied().unwrap_or(0.) + MARGIN;
+    let circle = |layer: &str, r: f64| {
+        format!("0\nCIRCLE\n8\n{layer}\n10\n0.0\n20\n0.0\n30\n0.0\n40\n{:.4}\n", r * 10.)
+    };
+
+    let mut out = String::from("0\nSECTION\n2\nHEADER\n9\n$INSUNITS\n70\n4\n0\nENDSEC\n");
+    out += "0\nSECTION\n2\nENTITIES\n";
+    for &r in radii {
+        out += &circle("ZONES", r);
+    }
+    out += &circle("OUTLINE", edge);
+    out += "0\nENDSEC\n0\nEOF\n";
+    out
+}
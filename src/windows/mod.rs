@@ -1,4 +1,5 @@
 pub mod doc_app;
 pub mod settings;
 pub mod main_app;
+pub mod mask_app;
 mod math;
\ No newline at end of file
@@ -3,8 +3,7 @@ use egui::Vec2;
 
 ///settings window (size of window & scale of elements)
 
-
-/// hight of window
+// hight of window
 #[derive(Default, PartialEq)]
 enum SizeType { 
     #[default]
@@ -1,5 +1,6 @@
 use crate::windows::main_app::MainApp;
 use crate::windows::doc_app::DocApp;
+use crate::windows::mask_app::MaskApp;
 use eframe::emath::Vec2;
 use egui::{Ui, UiBuilder, ViewportCommand, Visuals};
 #[cfg(debug_assertions)]
@@ -12,6 +13,7 @@ pub enum Anchor {
     #[default]
     Main,
     Doc,
+    Mask,
     Setting,
 }
 
@@ -21,6 +23,7 @@ pub enum Anchor {
 pub struct State {
     main: MainApp,
     doc: DocApp,
+    mask: MaskApp,
     settings: SettingsApp,
 
     selected_anchor: Anchor,
@@ -61,6 +64,11 @@ impl WrapApp {
                 Anchor::Doc,
                 &mut self.state.doc as &mut dyn eframe::App,
             ),
+            (
+                "Маска",
+                Anchor::Mask,
+                &mut self.state.mask as &mut dyn eframe::App,
+            ),
             (
                 "Настройки",
                 Anchor::Setting,